@@ -135,6 +135,19 @@ pub struct RouteConfigDto {
 #[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct ExtConfigDto {
   pub download_detour: String,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub dedup: Option<DedupConfigDto>,
+}
+
+/// Collapse nodes that point at the same server with the same credentials
+#[derive(Debug, Deserialize, Serialize, Clone, Default)]
+pub struct DedupConfigDto {
+  #[serde(default)]
+  pub enabled: bool,
+  /// Subscription UUIDs whose copy of a duplicated node is kept, highest priority first.
+  /// Subscriptions not listed fall back to the subscription order.
+  #[serde(default, skip_serializing_if = "Vec::is_empty")]
+  pub prefer_subscriptions: Vec<String>,
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -4,17 +4,25 @@ use axum::{
 };
 use serde::Deserialize;
 use serde_json::{Map, Value};
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::path::Path;
 use tokio::fs;
 
-use crate::backend::api::config::ConfigCreateDto;
-use crate::backend::api::filter::FilterCreateDto;
+use crate::backend::api::config::{ConfigCreateDto, DedupConfigDto};
+use crate::backend::api::config_validation::IssueKind;
+use crate::backend::api::filter::CompiledFilter;
+use crate::backend::api::generation_context::{
+  GenerationContext, OutboundKind, ParseCache, parse_cache, read_json_dir,
+};
+use crate::backend::api::node_overrides::{
+  NodeOverride, apply_node_overrides, load_node_overrides,
+};
 use crate::backend::api::outbound_group::OutboundGroupCreateDto;
+use crate::backend::api::rename_rules::load_global_rename_rules;
 use crate::backend::api::subscribe::SubscribeCreateDto;
 use crate::backend::error::AppError;
-
-const OUTBOUND_GROUP_DIR: &str = "./data/outbound-group";
+use crate::backend::pseudo_nodes::{PseudoNodeFilter, exclude_pseudo_nodes};
+use crate::backend::tag_template::{RenameRule, apply_tag_naming};
 
 /// Main handler for generating and downloading a complete sing-box config
 pub async fn generate_config(
@@ -25,28 +33,60 @@ pub async fn generate_config(
   // 1. Load config
   let config = load_config(&uuid).await?;
 
-  // 2. Build sing-box config as JSON object
+  // 2. Snapshot every module once so the whole generation reads consistent data
+  let mut context = GenerationContext::load().await?;
+
+  // 3. Build sing-box config
+  let singbox_config = build_config(&config, &mut context).await?;
+
+  // 4. Return as downloadable JSON
+  let safe_name = sanitize_filename(&config.name);
+  let filename = format!("{}.json", safe_name);
+
+  let mut response = Json(singbox_config).into_response();
+  response.headers_mut().insert(
+    "Content-Type",
+    "application/json"
+      .parse()
+      .map_err(|_| AppError::InternalServerError("Invalid header value".to_string()))?,
+  );
+  response.headers_mut().insert(
+    "Content-Disposition",
+    format!("attachment; filename=\"{}\"", filename)
+      .parse()
+      .map_err(|_| AppError::InternalServerError("Invalid header value".to_string()))?,
+  );
+
+  Ok(response)
+}
+
+/// Resolve every module of a config into a sing-box config
+pub(crate) async fn build_config(
+  config: &ConfigCreateDto,
+  context: &mut GenerationContext,
+) -> Result<Value, AppError> {
   let mut singbox_config = Map::new();
+  context.set_dedup(config.ext_config.dedup.clone());
 
-  // 3. Resolve each module
-  singbox_config.insert("log".to_string(), resolve_log(&config.log).await?);
-  singbox_config.insert("dns".to_string(), resolve_dns(&config.dns).await?);
+  singbox_config.insert("log".to_string(), resolve_log(context, &config.log)?);
+  singbox_config.insert("dns".to_string(), resolve_dns(context, &config.dns)?);
   singbox_config.insert(
     "inbounds".to_string(),
-    resolve_inbounds(&config.inbounds).await?,
+    resolve_inbounds(context, &config.inbounds)?,
   );
 
-  let (outbounds, route_final_tag) = resolve_outbounds_and_route(&config).await?;
-  let route_value = resolve_route(&config.route, &route_final_tag, &config.inbounds).await?;
+  let (outbounds, route_final_tag) = resolve_outbounds_and_route(config, context).await?;
+  let route_value = resolve_route(context, &config.route, &route_final_tag, &config.inbounds)?;
 
   // Filter out unused outbounds
-  let outbounds = filter_unused_outbounds(outbounds, &route_value, &route_final_tag);
+  let outbounds = filter_unused_outbounds(context, outbounds, &route_value, &route_final_tag);
 
   singbox_config.insert("outbounds".to_string(), outbounds);
   singbox_config.insert("route".to_string(), route_value);
 
   // Resolve download_detour tag
-  let download_detour_tag = resolve_download_detour_tag(&config.ext_config.download_detour).await?;
+  let download_detour_tag =
+    resolve_download_detour_tag(context, &config.ext_config.download_detour)?;
 
   // Inject download_detour into remote rule_set entries in route
   if let Some(route_obj) = singbox_config
@@ -56,7 +96,7 @@ pub async fn generate_config(
     inject_download_detour_to_rule_sets(route_obj, &download_detour_tag);
   }
 
-  let mut experimental_value = resolve_experimental(&config.experimental).await?;
+  let mut experimental_value = resolve_experimental(context, &config.experimental)?;
 
   // Inject download_detour into experimental.clash_api.external_ui_download_detour
   if let Some(exp_obj) = experimental_value.as_object_mut() {
@@ -70,29 +110,11 @@ pub async fn generate_config(
 
   singbox_config.insert("experimental".to_string(), experimental_value);
 
-  // 4. Return as downloadable JSON
-  let safe_name = sanitize_filename(&config.name);
-  let filename = format!("{}.json", safe_name);
-
-  let mut response = Json(Value::Object(singbox_config)).into_response();
-  response.headers_mut().insert(
-    "Content-Type",
-    "application/json"
-      .parse()
-      .map_err(|_| AppError::InternalServerError("Invalid header value".to_string()))?,
-  );
-  response.headers_mut().insert(
-    "Content-Disposition",
-    format!("attachment; filename=\"{}\"", filename)
-      .parse()
-      .map_err(|_| AppError::InternalServerError("Invalid header value".to_string()))?,
-  );
-
-  Ok(response)
+  Ok(Value::Object(singbox_config))
 }
 
 /// Load config file
-async fn load_config(uuid: &str) -> Result<ConfigCreateDto, AppError> {
+pub(crate) async fn load_config(uuid: &str) -> Result<ConfigCreateDto, AppError> {
   let file_path = Path::new("./data/configs").join(format!("{}.json", uuid));
 
   if !file_path.exists() {
@@ -105,98 +127,21 @@ async fn load_config(uuid: &str) -> Result<ConfigCreateDto, AppError> {
   Ok(config)
 }
 
-/// Load and parse a module's JSON field
-async fn load_module_json(module_type: &str, uuid: &str) -> Result<Value, AppError> {
-  let file_path = Path::new("./data")
-    .join(module_type)
-    .join(format!("{}.json", uuid));
-
-  if !file_path.exists() {
-    return Err(AppError::NotFound(format!(
-      "{} module not found: {}",
-      module_type, uuid
-    )));
-  }
-
-  let content = fs::read_to_string(&file_path).await?;
-  let module: Value = serde_json::from_str(&content)?;
-
-  // Extract and parse the "json" field
-  if let Some(json_str) = module.get("json").and_then(|j| j.as_str()) {
-    let parsed: Value = serde_json::from_str(json_str).map_err(|e| {
-      AppError::InternalServerError(format!(
-        "Failed to parse {} module JSON: {}",
-        module_type, e
-      ))
-    })?;
-    Ok(parsed)
-  } else {
-    Err(AppError::InternalServerError(format!(
-      "{} module missing 'json' field",
-      module_type
-    )))
-  }
-}
-
-/// Load a module's JSON field, injecting the module's "name" as "tag" if not present in the JSON.
-/// This is used for outbound modules where the JSON may not contain a "tag" field.
-async fn load_module_json_with_tag(module_type: &str, uuid: &str) -> Result<Value, AppError> {
-  let file_path = Path::new("./data")
-    .join(module_type)
-    .join(format!("{}.json", uuid));
-
-  if !file_path.exists() {
-    return Err(AppError::NotFound(format!(
-      "{} module not found: {}",
-      module_type, uuid
-    )));
-  }
-
-  let content = fs::read_to_string(&file_path).await?;
-  let module: Value = serde_json::from_str(&content)?;
-
-  let module_name = module
-    .get("name")
-    .and_then(|n| n.as_str())
-    .unwrap_or("")
-    .to_string();
-
-  if let Some(json_str) = module.get("json").and_then(|j| j.as_str()) {
-    let mut parsed: Value = serde_json::from_str(json_str).map_err(|e| {
-      AppError::InternalServerError(format!(
-        "Failed to parse {} module JSON: {}",
-        module_type, e
-      ))
-    })?;
-
-    // Inject "tag" from module "name" if not already present
-    if let Some(obj) = parsed.as_object_mut() {
-      if !obj.contains_key("tag") && !module_name.is_empty() {
-        obj.insert("tag".to_string(), Value::String(module_name));
-      }
-    }
-
-    Ok(parsed)
-  } else {
-    Err(AppError::InternalServerError(format!(
-      "{} module missing 'json' field",
-      module_type
-    )))
-  }
-}
-
 /// Resolve log module
-async fn resolve_log(uuid: &str) -> Result<Value, AppError> {
-  load_module_json("logs", uuid).await
+fn resolve_log(context: &GenerationContext, uuid: &str) -> Result<Value, AppError> {
+  context.module_json("logs", uuid)
 }
 
 /// Resolve DNS configuration
-async fn resolve_dns(dns: &crate::backend::api::config::DnsConfigDto) -> Result<Value, AppError> {
+fn resolve_dns(
+  context: &mut GenerationContext,
+  dns: &crate::backend::api::config::DnsConfigDto,
+) -> Result<Value, AppError> {
   let mut dns_config = Map::new();
 
   // Merge dns-config if provided
   if let Some(config_uuid) = &dns.config {
-    let config_value = load_module_json("dns-config", config_uuid).await?;
+    let config_value = context.module_json("dns-config", config_uuid)?;
     if let Value::Object(config_obj) = config_value {
       for (key, value) in config_obj {
         dns_config.insert(key, value);
@@ -205,12 +150,23 @@ async fn resolve_dns(dns: &crate::backend::api::config::DnsConfigDto) -> Result<
   }
 
   // Resolve DNS servers
+  let mut server_tags = TagRegistry::new("DNS server");
   let mut servers = Vec::new();
   for server_entry in &dns.servers {
-    let mut server = load_module_json("dns-server", &server_entry.uuid).await?;
+    let mut server = context.module_json("dns-server", &server_entry.uuid)?;
+    // A server selected twice is only emitted once
+    if let Some(tag) = server.get("tag").and_then(|t| t.as_str())
+      && !server_tags.claim(
+        context,
+        tag,
+        TagSource::DnsServer(server_entry.uuid.clone()),
+      )?
+    {
+      continue;
+    }
     // Inject detour if configured
     if let Some(detour_uuid) = &server_entry.detour {
-      let detour_tag = resolve_outbound_uuid_to_tag(detour_uuid).await?;
+      let detour_tag = context.outbound_tag(detour_uuid)?;
       if !detour_tag.is_empty() {
         if let Some(obj) = server.as_object_mut() {
           obj.insert("detour".to_string(), Value::String(detour_tag));
@@ -233,7 +189,7 @@ async fn resolve_dns(dns: &crate::backend::api::config::DnsConfigDto) -> Result<
         // Resolve rulesets - collect tag names only
         let mut rule_set_tags = Vec::new();
         for ruleset_uuid in &rule.rule_set {
-          let ruleset = load_module_json_with_tag("rulesets", ruleset_uuid).await?;
+          let ruleset = context.module_json_with_tag("rulesets", ruleset_uuid)?;
           if let Some(tag) = ruleset.get("tag").and_then(|t| t.as_str()) {
             rule_set_tags.push(Value::String(tag.to_string()));
           }
@@ -241,7 +197,7 @@ async fn resolve_dns(dns: &crate::backend::api::config::DnsConfigDto) -> Result<
         rule_obj.insert("rule_set".to_string(), Value::Array(rule_set_tags));
 
         // Resolve server tag
-        let server = load_module_json("dns-server", &rule.server).await?;
+        let server = context.module_json("dns-server", &rule.server)?;
         if let Some(tag) = server.get("tag").and_then(|t| t.as_str()) {
           rule_obj.insert("server".to_string(), Value::String(tag.to_string()));
         }
@@ -253,7 +209,7 @@ async fn resolve_dns(dns: &crate::backend::api::config::DnsConfigDto) -> Result<
   }
 
   // Resolve final server tag
-  let final_server = load_module_json("dns-server", &dns.final_server).await?;
+  let final_server = context.module_json("dns-server", &dns.final_server)?;
   if let Some(tag) = final_server.get("tag").and_then(|t| t.as_str()) {
     dns_config.insert("final".to_string(), Value::String(tag.to_string()));
   }
@@ -262,41 +218,28 @@ async fn resolve_dns(dns: &crate::backend::api::config::DnsConfigDto) -> Result<
 }
 
 /// Resolve inbounds array
-async fn resolve_inbounds(inbound_uuids: &[String]) -> Result<Value, AppError> {
+fn resolve_inbounds(
+  context: &mut GenerationContext,
+  inbound_uuids: &[String],
+) -> Result<Value, AppError> {
+  let mut inbound_tags = TagRegistry::new("inbound");
   let mut inbounds = Vec::new();
   for uuid in inbound_uuids {
-    let inbound = load_module_json("inbounds", uuid).await?;
+    let inbound = context.module_json("inbounds", uuid)?;
+    // An inbound selected twice is only emitted once
+    if let Some(tag) = inbound.get("tag").and_then(|t| t.as_str())
+      && !inbound_tags.claim(context, tag, TagSource::Inbound(uuid.clone()))?
+    {
+      continue;
+    }
     inbounds.push(inbound);
   }
   Ok(Value::Array(inbounds))
 }
 
 /// Resolve experimental module
-async fn resolve_experimental(uuid: &str) -> Result<Value, AppError> {
-  load_module_json("experimentals", uuid).await
-}
-
-/// Check if UUID is an outbound group
-async fn is_outbound_group(uuid: &str) -> Result<bool, AppError> {
-  let file_path = Path::new(OUTBOUND_GROUP_DIR).join(format!("{}.json", uuid));
-  Ok(file_path.exists())
-}
-
-/// Load outbound group
-async fn load_outbound_group(uuid: &str) -> Result<OutboundGroupCreateDto, AppError> {
-  let file_path = Path::new(OUTBOUND_GROUP_DIR).join(format!("{}.json", uuid));
-
-  if !file_path.exists() {
-    return Err(AppError::NotFound(format!(
-      "Outbound group not found: {}",
-      uuid
-    )));
-  }
-
-  let content = fs::read_to_string(&file_path).await?;
-  let group: OutboundGroupCreateDto = serde_json::from_str(&content)?;
-
-  Ok(group)
+fn resolve_experimental(context: &GenerationContext, uuid: &str) -> Result<Value, AppError> {
+  context.module_json("experimentals", uuid)
 }
 
 /// Convert outbound group to sing-box format
@@ -342,44 +285,9 @@ fn convert_group_to_singbox(group: &OutboundGroupCreateDto, outbound_tags: &[Str
   Value::Object(obj)
 }
 
-/// Check if UUID is a filter
-async fn is_filter(uuid: &str) -> Result<bool, AppError> {
-  let file_path = Path::new("./data/filters").join(format!("{}.json", uuid));
-  Ok(file_path.exists())
-}
-
-/// Load filter
-async fn load_filter(uuid: &str) -> Result<FilterCreateDto, AppError> {
-  let file_path = Path::new("./data/filters").join(format!("{}.json", uuid));
-  if !file_path.exists() {
-    return Err(AppError::NotFound(format!("Filter not found: {}", uuid)));
-  }
-
-  let content = fs::read_to_string(&file_path).await?;
-  let filter: FilterCreateDto = serde_json::from_str(&content)?;
-  Ok(filter)
-}
-
-/// Decode base64-encoded subscription content to a UTF-8 string.
-/// Handles both padded and unpadded base64 (many subscription servers omit padding).
-pub fn decode_base64_content(content: &str) -> Option<String> {
-  let content = content.trim();
-  if content.is_empty() {
-    return None;
-  }
-  // Try standard base64 (with padding) first
-  if let Ok(decoded) = base64::Engine::decode(&base64::engine::general_purpose::STANDARD, content) {
-    return String::from_utf8(decoded).ok();
-  }
-  // Fall back to base64 without padding requirement
-  let decoded =
-    base64::Engine::decode(&base64::engine::general_purpose::STANDARD_NO_PAD, content).ok()?;
-  String::from_utf8(decoded).ok()
-}
-
 /// Read subscribe order from file
-async fn read_subscribe_order() -> Result<Vec<String>, AppError> {
-  let order_path = Path::new("./data/subscribes/.order.json");
+async fn read_subscribe_order(dir_path: &Path) -> Result<Vec<String>, AppError> {
+  let order_path = dir_path.join(".order.json");
   if !order_path.exists() {
     return Ok(Vec::new());
   }
@@ -394,153 +302,316 @@ async fn read_subscribe_order() -> Result<Vec<String>, AppError> {
   Ok(order.uuids)
 }
 
-/// Get all subscription outbounds
-async fn get_subscription_outbounds() -> Result<Vec<Value>, AppError> {
-  let dir_path = Path::new("./data/subscribes");
-  if !dir_path.exists() {
-    return Ok(Vec::new());
-  }
+/// Read every subscription file in `dir_path` once: those listed in the saved order first,
+/// then any others (new ones). Files that fail to parse are skipped.
+pub(crate) async fn read_subscriptions(
+  dir_path: &Path,
+) -> Result<Vec<SubscribeCreateDto>, AppError> {
+  let mut subscribes: Vec<SubscribeCreateDto> = read_json_dir(dir_path)
+    .await?
+    .into_iter()
+    .filter_map(|(_, content)| serde_json::from_str(&content).ok())
+    .collect();
 
-  // Read subscription order
-  let order = read_subscribe_order().await?;
-  let mut all_outbounds = Vec::new();
+  let order = read_subscribe_order(dir_path).await?;
+  let position = |uuid: &str| order.iter().position(|u| u == uuid).unwrap_or(order.len());
+  subscribes.sort_by_key(|subscribe| position(&subscribe.uuid));
+  Ok(subscribes)
+}
 
-  // First, process subscriptions in order
-  for uuid in &order {
-    let file_path = dir_path.join(format!("{}.json", uuid));
-    if !file_path.exists() {
-      continue;
-    }
+/// Get the outbounds of every enabled subscription in order
+pub(crate) async fn get_subscription_outbounds() -> Result<Vec<SubscriptionOutbounds>, AppError> {
+  let subscribes = read_subscriptions(Path::new("./data/subscribes")).await?;
+  let global_rules = load_global_rename_rules().await?;
+  let overrides = load_node_overrides().await?;
 
-    if let Ok(content) = fs::read_to_string(&file_path).await {
-      if let Ok(subscribe) = serde_json::from_str::<SubscribeCreateDto>(&content) {
-        let outbounds = process_subscription(&subscribe).await?;
-        all_outbounds.extend(outbounds);
-      }
+  let mut cache = parse_cache();
+  // Parse results of subscriptions that no longer exist are never needed again
+  cache.retain(subscribes.iter().map(|s| s.uuid.as_str()));
+  process_subscriptions(&subscribes, &global_rules, &overrides, &mut cache)
+}
+
+/// Parse, name and patch the nodes of each enabled subscription, in the given order
+pub(crate) fn process_subscriptions(
+  subscribes: &[SubscribeCreateDto],
+  global_rules: &[RenameRule],
+  overrides: &[NodeOverride],
+  cache: &mut ParseCache,
+) -> Result<Vec<SubscriptionOutbounds>, AppError> {
+  let mut subscriptions = Vec::new();
+  let mut used_tags = HashSet::new();
+  for subscribe in subscribes {
+    if let Some(processed) =
+      process_subscription(subscribe, global_rules, overrides, &mut used_tags, cache)?
+    {
+      subscriptions.push(processed);
     }
   }
+  Ok(subscriptions)
+}
 
-  // Then, process any subscriptions not in order (new ones)
-  let mut entries = fs::read_dir(dir_path).await?;
-  while let Some(entry) = entries.next_entry().await? {
-    let path = entry.path();
-    if path.extension().and_then(|s| s.to_str()) == Some("json") {
-      if let Some(file_name) = path.file_stem().and_then(|s| s.to_str()) {
-        // Skip if already processed
-        if order.contains(&file_name.to_string()) {
-          continue;
-        }
+/// The subscriptions `filter` draws from
+pub(crate) fn filter_subscriptions<'a>(
+  filter: &CompiledFilter,
+  subscriptions: &'a [SubscriptionOutbounds],
+) -> Vec<&'a SubscriptionOutbounds> {
+  subscriptions
+    .iter()
+    .filter(|subscription| filter.draws_from(&subscription.uuid))
+    .collect()
+}
 
-        if let Ok(content) = fs::read_to_string(&path).await {
-          if let Ok(subscribe) = serde_json::from_str::<SubscribeCreateDto>(&content) {
-            let outbounds = process_subscription(&subscribe).await?;
-            all_outbounds.extend(outbounds);
-          }
-        }
+/// Deduplicate the merged nodes of every subscription when `dedup` is enabled.
+/// This runs once before any filter, so every filter sees the same surviving copy of a node.
+pub(crate) fn dedup_subscriptions(
+  subscriptions: Vec<SubscriptionOutbounds>,
+  dedup: Option<&DedupConfigDto>,
+) -> Vec<SubscriptionOutbounds> {
+  match dedup {
+    Some(dedup) if dedup.enabled => dedup_outbounds(subscriptions, &dedup.prefer_subscriptions),
+    _ => subscriptions,
+  }
+}
+
+/// Identity of a node for deduplication: protocol, server, port and credentials.
+/// Tags are deliberately ignored since each subscription names nodes differently.
+pub(crate) fn node_identity(outbound: &Value) -> String {
+  let field = |key: &str| match outbound.get(key) {
+    Some(Value::String(s)) => s.clone(),
+    Some(Value::Null) | None => String::new(),
+    Some(other) => other.to_string(),
+  };
+  [
+    field("type"),
+    field("server").to_ascii_lowercase(),
+    field("server_port"),
+    field("uuid"),
+    field("password"),
+    field("method"),
+    field("username"),
+  ]
+  .join("|")
+}
+
+/// Processed nodes of one subscription
+#[derive(Clone)]
+pub(crate) struct SubscriptionOutbounds {
+  pub uuid: String,
+  pub name: String,
+  pub priority: i32,
+  pub outbounds: Vec<Value>,
+}
+
+/// Drop duplicated nodes across (and within) subscriptions.
+/// The copy kept comes from the first subscription in `prefer`, then the highest `priority`,
+/// then the subscription order; surviving nodes keep their original position.
+pub(crate) fn dedup_outbounds(
+  subscriptions: Vec<SubscriptionOutbounds>,
+  prefer: &[String],
+) -> Vec<SubscriptionOutbounds> {
+  let rank = |subscription: &SubscriptionOutbounds| {
+    let preferred = prefer
+      .iter()
+      .position(|p| *p == subscription.uuid)
+      .unwrap_or(prefer.len());
+    (preferred, std::cmp::Reverse(subscription.priority))
+  };
+  let mut by_priority: Vec<usize> = (0..subscriptions.len()).collect();
+  by_priority.sort_by_key(|&i| rank(&subscriptions[i]));
+
+  let mut seen = HashSet::new();
+  let mut keep: HashSet<(usize, usize)> = HashSet::new();
+  for i in by_priority {
+    for (j, outbound) in subscriptions[i].outbounds.iter().enumerate() {
+      if seen.insert(node_identity(outbound)) {
+        keep.insert((i, j));
       }
     }
   }
 
-  Ok(all_outbounds)
+  subscriptions
+    .into_iter()
+    .enumerate()
+    .map(|(i, subscription)| SubscriptionOutbounds {
+      outbounds: subscription
+        .outbounds
+        .into_iter()
+        .enumerate()
+        .filter(|(j, _)| keep.contains(&(i, *j)))
+        .map(|(_, outbound)| outbound)
+        .collect(),
+      ..subscription
+    })
+    .collect()
 }
 
-/// Process a single subscription and return its outbounds
-async fn process_subscription(subscribe: &SubscribeCreateDto) -> Result<Vec<Value>, AppError> {
+/// Process a single subscription and return its outbounds, or None if it is disabled.
+/// `used_tags` carries the tags taken by earlier subscriptions for collision handling.
+fn process_subscription(
+  subscribe: &SubscribeCreateDto,
+  global_rules: &[RenameRule],
+  overrides: &[NodeOverride],
+  used_tags: &mut HashSet<String>,
+  cache: &mut ParseCache,
+) -> Result<Option<SubscriptionOutbounds>, AppError> {
   let subscribe_name = subscribe.name.clone();
-  let mut outbounds = Vec::new();
 
   // Parse subscription metadata
-  if let Ok(metadata) = serde_json::from_str::<Value>(&subscribe.json) {
-    // Skip disabled subscriptions (enabled defaults to true if not set)
-    if metadata.get("enabled").and_then(|e| e.as_bool()) == Some(false) {
-      return Ok(outbounds);
-    }
+  let (Ok(typed), Ok(metadata)) = (
+    subscribe.metadata(),
+    serde_json::from_str::<Value>(&subscribe.json),
+  ) else {
+    log::warn!("Skipping subscription {}: invalid metadata", subscribe_name);
+    return Ok(None);
+  };
 
-    if let Some(content_str) = metadata.get("content").and_then(|c| c.as_str()) {
-      // Decode base64 content
-      if let Some(decoded_str) = decode_base64_content(content_str) {
-        // Parse subscription format (ss://, trojan://, etc.)
-        for line in decoded_str.lines() {
-          let line = line.trim();
-          if line.is_empty() {
-            continue;
-          }
+  // Skip disabled subscriptions (enabled defaults to true if not set)
+  if !typed.enabled {
+    return Ok(None);
+  }
 
-          // Parse different formats and convert to sing-box outbound
-          if let Ok(mut outbound) = parse_subscription_line(line) {
-            // Rename tag to "original-name-subscription-name" format
-            if let Some(obj) = outbound.as_object_mut() {
-              if let Some(original_tag) = obj.get("tag").and_then(|t| t.as_str()) {
-                let new_tag = format!("{}-{}", original_tag, subscribe_name);
-                obj.insert("tag".to_string(), Value::String(new_tag));
-              }
-            }
-            outbounds.push(outbound);
-          }
-        }
-      }
+  // Parse subscription content (base64 URI list, Clash YAML or sing-box JSON)
+  let parsed = cache.parse(&subscribe.uuid, &typed.content);
+  for diagnostic in &parsed.diagnostics {
+    log::warn!(
+      "Skipped line {} of subscription {}: {}",
+      diagnostic.line_index,
+      subscribe_name,
+      diagnostic.message
+    );
+  }
+  // Drop informational entries such as "剩余流量" before they reach filters
+  let (nodes, excluded) = exclude_pseudo_nodes(
+    parsed.outbounds,
+    &PseudoNodeFilter::from_metadata(&metadata),
+  );
+  if !excluded.is_empty() {
+    log::info!(
+      "Dropped {} informational nodes from subscription {}",
+      excluded.len(),
+      subscribe_name
+    );
+  }
+
+  // Clean names with the rename rules, then apply the tag template (default "{name}-{sub}")
+  let naming = typed.tag_naming(global_rules);
+  let mut outbounds = apply_tag_naming(nodes, &subscribe_name, &naming, used_tags)?;
+
+  // Local tweaks (utls fingerprint, detour, ...) from the override rules
+  apply_node_overrides(&mut outbounds, &subscribe.uuid, overrides);
+
+  Ok(Some(SubscriptionOutbounds {
+    uuid: subscribe.uuid.clone(),
+    name: subscribe_name,
+    priority: typed.priority,
+    outbounds,
+  }))
+}
+
+/// Copy the subscription outbounds that pass `filter`, sorted and limited as it asks
+pub(crate) fn filter_outbounds(
+  filter: &CompiledFilter,
+  subscriptions: &[SubscriptionOutbounds],
+) -> Vec<Value> {
+  let mut outbounds: Vec<Value> = subscriptions
+    .iter()
+    .flat_map(|subscription| {
+      subscription
+        .outbounds
+        .iter()
+        .filter(|outbound| filter.matches(outbound, &subscription.uuid))
+        .cloned()
+    })
+    .collect();
+  filter.arrange(&mut outbounds);
+  outbounds
+}
+
+/// Where a generated tag came from, for collision errors
+#[derive(Debug, Clone)]
+enum TagSource {
+  Outbound(String),
+  Group(String),
+  /// A subscription node, by the filter that selected it
+  Node(String),
+  Inbound(String),
+  DnsServer(String),
+}
+
+impl TagSource {
+  /// Subscription nodes already have unique tags, so the same node reached through two
+  /// filters is one source
+  fn same_as(&self, other: &TagSource) -> bool {
+    match (self, other) {
+      (TagSource::Node(_), TagSource::Node(_)) => true,
+      (TagSource::Outbound(a), TagSource::Outbound(b))
+      | (TagSource::Group(a), TagSource::Group(b))
+      | (TagSource::Inbound(a), TagSource::Inbound(b))
+      | (TagSource::DnsServer(a), TagSource::DnsServer(b)) => a == b,
+      _ => false,
     }
   }
 
-  Ok(outbounds)
+  fn describe(&self) -> String {
+    match self {
+      TagSource::Outbound(uuid) => format!("outbound {}", uuid),
+      TagSource::Group(uuid) => format!("outbound group {}", uuid),
+      TagSource::Node(filter) => format!("a subscription node selected by filter {}", filter),
+      TagSource::Inbound(uuid) => format!("inbound {}", uuid),
+      TagSource::DnsServer(uuid) => format!("DNS server {}", uuid),
+    }
+  }
 }
 
-// Re-export subscription parser from shared module
-use crate::backend::subscription_parser::parse_subscription_line;
-
-/// Apply filter to subscription outbounds
-async fn apply_filter(filter: &FilterCreateDto) -> Result<Vec<Value>, AppError> {
-  let all_outbounds = get_subscription_outbounds().await?;
-  let mut filtered = Vec::new();
-
-  for outbound in all_outbounds {
-    if let Some(tag) = outbound.get("tag").and_then(|t| t.as_str()) {
-      let matches = match filter.filter_type.as_str() {
-        "simple" => {
-          // Simple contains match for any pattern part
-          let pattern_match = filter
-            .pattern
-            .split('|')
-            .any(|pattern| tag.contains(pattern.trim()));
-
-          // If pattern matches and except is configured, check if should be excluded
-          if pattern_match {
-            if let Some(except_pattern) = &filter.except {
-              // Exclude if any except pattern matches
-              !except_pattern
-                .split('|')
-                .any(|pattern| tag.contains(pattern.trim()))
-            } else {
-              // No except pattern, include the match
-              true
-            }
-          } else {
-            false
-          }
-        }
-        "regex" => {
-          // Regex match (except not supported for regex type)
-          if let Ok(re) = regex::Regex::new(&filter.pattern) {
-            re.is_match(tag)
-          } else {
-            false
-          }
-        }
-        _ => false,
-      };
+/// Tags generated in one sing-box namespace (outbounds, inbounds or DNS servers)
+struct TagRegistry {
+  namespace: &'static str,
+  owners: HashMap<String, TagSource>,
+}
 
-      if matches {
-        filtered.push(outbound);
-      }
+impl TagRegistry {
+  fn new(namespace: &'static str) -> Self {
+    Self {
+      namespace,
+      owners: HashMap::new(),
     }
   }
 
-  Ok(filtered)
+  /// Record a tag; false if the same source already has it, an error if another source does
+  fn claim(
+    &mut self,
+    context: &mut GenerationContext,
+    tag: &str,
+    source: TagSource,
+  ) -> Result<bool, AppError> {
+    // Outbounds without a tag are dropped later with a warning
+    if tag.is_empty() {
+      return Ok(true);
+    }
+    match self.owners.get(tag) {
+      None => {
+        self.owners.insert(tag.to_string(), source);
+        Ok(true)
+      }
+      Some(owner) if owner.same_as(&source) => Ok(false),
+      Some(owner) => Err(context.reject(
+        IssueKind::DuplicateTag,
+        format!(
+          "Duplicate {} tag \"{}\": used by {} and {}",
+          self.namespace,
+          tag,
+          owner.describe(),
+          source.describe()
+        ),
+      )),
+    }
+  }
 }
 
 /// Resolve outbounds and route together (they depend on each other)
 async fn resolve_outbounds_and_route(
   config: &ConfigCreateDto,
+  context: &mut GenerationContext,
 ) -> Result<(Value, String), AppError> {
   let mut outbound_uuids = HashSet::new();
 
@@ -565,7 +636,7 @@ async fn resolve_outbounds_and_route(
   outbound_uuids.insert(final_uuid.clone());
 
   // Use iterative approach with a work queue to handle nested groups
-  let mut collected_tags = HashSet::new();
+  let mut collected_tags = TagRegistry::new("outbound");
   let mut outbounds = Vec::new();
   let mut groups_to_add = Vec::new();
   let mut work_queue: Vec<String> = outbound_uuids.iter().cloned().collect();
@@ -578,8 +649,9 @@ async fn resolve_outbounds_and_route(
       continue;
     }
 
-    if is_outbound_group(&uuid).await? {
-      let group = load_outbound_group(&uuid).await?;
+    let kind = context.outbound_kind(&uuid)?;
+    if kind == OutboundKind::Group {
+      let group = context.group(&uuid)?;
       let group_tag = group.name.clone();
 
       // Store final tag if this is the final outbound
@@ -587,23 +659,20 @@ async fn resolve_outbounds_and_route(
         final_tag = group_tag.clone();
       }
 
-      // Skip duplicate group tags
-      if collected_tags.contains(&group_tag) {
-        continue;
-      }
+      collected_tags.claim(context, &group_tag, TagSource::Group(uuid.clone()))?;
 
       // Collect member tags and add members to work queue
       let mut member_tags = Vec::new();
       for member_uuid in &group.outbounds {
         // Check if it's a group, filter, or outbound
-        if is_outbound_group(member_uuid).await? {
-          let member_group = load_outbound_group(member_uuid).await?;
+        let member_kind = context.outbound_kind(member_uuid)?;
+        if member_kind == OutboundKind::Group {
+          let member_group = context.group(member_uuid)?;
           member_tags.push(member_group.name.clone());
           work_queue.push(member_uuid.clone());
-        } else if is_filter(member_uuid).await? {
+        } else if member_kind == OutboundKind::Filter {
           // It's a filter - apply to subscriptions
-          let filter = load_filter(member_uuid).await?;
-          let filtered_outbounds = apply_filter(&filter).await?;
+          let filtered_outbounds = context.apply_filter(member_uuid).await?;
 
           // Add all filtered outbounds
           for outbound_json in filtered_outbounds {
@@ -615,14 +684,13 @@ async fn resolve_outbounds_and_route(
             member_tags.push(tag.clone());
 
             // Add only if not already collected
-            if !collected_tags.contains(&tag) {
-              collected_tags.insert(tag);
+            if collected_tags.claim(context, &tag, TagSource::Node(member_uuid.clone()))? {
               outbounds.push(outbound_json);
             }
           }
         } else {
           // It's an individual outbound
-          let outbound_json = load_module_json_with_tag("outbounds", member_uuid).await?;
+          let outbound_json = context.module_json_with_tag("outbounds", member_uuid)?;
           let tag = outbound_json
             .get("tag")
             .and_then(|t| t.as_str())
@@ -631,27 +699,24 @@ async fn resolve_outbounds_and_route(
           member_tags.push(tag.clone());
 
           // Add only if not already collected
-          if !collected_tags.contains(&tag) {
-            collected_tags.insert(tag);
+          if collected_tags.claim(context, &tag, TagSource::Outbound(member_uuid.clone()))? {
             outbounds.push(outbound_json);
           }
         }
       }
 
-      // Store group with its member tags, mark tag as collected
-      collected_tags.insert(group_tag);
+      // Store group with its member tags
       groups_to_add.push((group, member_tags));
-    } else if is_filter(&uuid).await? {
+    } else if kind == OutboundKind::Filter {
       // It's a filter - apply to subscriptions
-      let filter = load_filter(&uuid).await?;
-      let filter_tag = filter.name.clone();
+      let filter_tag = context.filter(&uuid)?.name.clone();
 
       // Store final tag if this is the final outbound
       if uuid == final_uuid {
         final_tag = filter_tag;
       }
 
-      let filtered_outbounds = apply_filter(&filter).await?;
+      let filtered_outbounds = context.apply_filter(&uuid).await?;
       for outbound_json in filtered_outbounds {
         let tag = outbound_json
           .get("tag")
@@ -660,14 +725,13 @@ async fn resolve_outbounds_and_route(
           .to_string();
 
         // Add only if not already collected
-        if !collected_tags.contains(&tag) {
-          collected_tags.insert(tag);
+        if collected_tags.claim(context, &tag, TagSource::Node(uuid.clone()))? {
           outbounds.push(outbound_json);
         }
       }
     } else {
       // It's an individual outbound
-      let outbound_json = load_module_json_with_tag("outbounds", &uuid).await?;
+      let outbound_json = context.module_json_with_tag("outbounds", &uuid)?;
       let tag = outbound_json
         .get("tag")
         .and_then(|t| t.as_str())
@@ -680,8 +744,7 @@ async fn resolve_outbounds_and_route(
       }
 
       // Add only if not already collected
-      if !collected_tags.contains(&tag) {
-        collected_tags.insert(tag);
+      if collected_tags.claim(context, &tag, TagSource::Outbound(uuid.clone()))? {
         outbounds.push(outbound_json);
       }
     }
@@ -705,7 +768,10 @@ async fn resolve_outbounds_and_route(
       break;
     }
     for tag in &newly_empty {
-      log::warn!("Skipping empty outbound group: {}", tag);
+      context.warn(
+        IssueKind::EmptyGroup,
+        format!("Skipping empty outbound group: {}", tag),
+      );
     }
     removed_group_tags.extend(newly_empty);
   }
@@ -727,7 +793,12 @@ async fn resolve_outbounds_and_route(
 }
 
 /// Filter out outbounds that are not referenced by any route rule, route final, or group
-fn filter_unused_outbounds(outbounds: Value, route: &Value, final_tag: &str) -> Value {
+fn filter_unused_outbounds(
+  context: &mut GenerationContext,
+  outbounds: Value,
+  route: &Value,
+  final_tag: &str,
+) -> Value {
   let outbounds_arr = match outbounds {
     Value::Array(arr) => arr,
     _ => return outbounds,
@@ -769,7 +840,10 @@ fn filter_unused_outbounds(outbounds: Value, route: &Value, final_tag: &str) ->
     .filter(|outbound| {
       let tag = outbound.get("tag").and_then(|t| t.as_str()).unwrap_or("");
       if tag.is_empty() {
-        log::warn!("Removing outbound with empty tag: {:?}", outbound);
+        context.warn(
+          IssueKind::EmptyTag,
+          format!("Removing outbound with empty tag: {}", outbound),
+        );
         return false;
       }
       referenced_tags.contains(tag)
@@ -779,37 +853,9 @@ fn filter_unused_outbounds(outbounds: Value, route: &Value, final_tag: &str) ->
   Value::Array(filtered)
 }
 
-/// Resolve an outbound UUID to its tag (outbound_group name or outbound tag)
-async fn resolve_outbound_uuid_to_tag(uuid: &str) -> Result<String, AppError> {
-  if is_outbound_group(uuid).await? {
-    let group = load_outbound_group(uuid).await?;
-    Ok(group.name)
-  } else {
-    let outbound = load_module_json_with_tag("outbounds", uuid).await?;
-    Ok(
-      outbound
-        .get("tag")
-        .and_then(|t| t.as_str())
-        .unwrap_or("")
-        .to_string(),
-    )
-  }
-}
-
-/// Resolve inbound UUID to its tag
-async fn resolve_inbound_uuid_to_tag(uuid: &str) -> Result<String, AppError> {
-  let inbound = load_module_json_with_tag("inbounds", uuid).await?;
-  Ok(
-    inbound
-      .get("tag")
-      .and_then(|t| t.as_str())
-      .unwrap_or("")
-      .to_string(),
-  )
-}
-
 /// Resolve route configuration
-async fn resolve_route(
+fn resolve_route(
+  context: &mut GenerationContext,
   route: &crate::backend::api::config::RouteConfigDto,
   final_tag: &str,
   selected_inbounds: &[String],
@@ -818,7 +864,7 @@ async fn resolve_route(
 
   // Merge route-config if provided
   if let Some(config_uuid) = &route.config {
-    let config_value = load_module_json("routes", config_uuid).await?;
+    let config_value = context.module_json("routes", config_uuid)?;
     if let Value::Object(config_obj) = config_value {
       for (key, value) in config_obj {
         route_config.insert(key, value);
@@ -841,7 +887,7 @@ async fn resolve_route(
             // Resolve rulesets
             let mut rule_set_tags = Vec::new();
             for ruleset_uuid in rulesets {
-              let ruleset = load_module_json_with_tag("rulesets", ruleset_uuid).await?;
+              let ruleset = context.module_json_with_tag("rulesets", ruleset_uuid)?;
               if let Some(tag) = ruleset.get("tag").and_then(|t| t.as_str()) {
                 rule_set_tags.push(Value::String(tag.to_string()));
 
@@ -855,13 +901,16 @@ async fn resolve_route(
             rule_obj.insert("rule_set".to_string(), Value::Array(rule_set_tags));
 
             // Resolve outbound tag
-            let outbound_tag = resolve_outbound_uuid_to_tag(outbound).await?;
+            let outbound_tag = context.outbound_tag(outbound)?;
 
             // Skip rules with empty outbound tag
             if outbound_tag.is_empty() {
-              log::warn!(
-                "Skipping route rule with empty outbound tag for UUID: {}",
-                outbound
+              context.warn(
+                IssueKind::EmptyTag,
+                format!(
+                  "Skipping route rule with empty outbound tag for UUID: {}",
+                  outbound
+                ),
               );
               continue;
             }
@@ -875,11 +924,11 @@ async fn resolve_route(
             inbound,
           } => {
             // Load rule module JSON - it's a complete SingBox rule object
-            let mut rule_obj_value = load_module_json("rules", rule_uuid).await?;
+            let mut rule_obj_value = context.module_json("rules", rule_uuid)?;
 
             // If outbound UUID is specified, resolve and override outbound in the rule
             if let Some(outbound_uuid) = outbound {
-              let outbound_tag = resolve_outbound_uuid_to_tag(outbound_uuid).await?;
+              let outbound_tag = context.outbound_tag(outbound_uuid)?;
               if !outbound_tag.is_empty() {
                 if let Some(obj) = rule_obj_value.as_object_mut() {
                   obj.insert("outbound".to_string(), Value::String(outbound_tag));
@@ -890,16 +939,23 @@ async fn resolve_route(
             // If inbound UUID is specified and still in selected inbounds, inject its tag
             if let Some(inbound_uuid) = inbound {
               if selected_inbounds.contains(inbound_uuid) {
-                let inbound_tag = resolve_inbound_uuid_to_tag(inbound_uuid).await?;
-                if !inbound_tag.is_empty() {
-                  if let Some(obj) = rule_obj_value.as_object_mut() {
-                    obj.insert("inbound".to_string(), Value::String(inbound_tag));
-                  }
+                let inbound = context.module_json_with_tag("inbounds", inbound_uuid)?;
+                if let Some(inbound_tag) = inbound.get("tag").and_then(|t| t.as_str())
+                  && !inbound_tag.is_empty()
+                  && let Some(obj) = rule_obj_value.as_object_mut()
+                {
+                  obj.insert(
+                    "inbound".to_string(),
+                    Value::String(inbound_tag.to_string()),
+                  );
                 }
               } else {
-                log::warn!(
-                  "Inbound UUID {} not in selected inbounds, skipping inbound injection for rule",
-                  inbound_uuid
+                context.warn(
+                  IssueKind::UnselectedInbound,
+                  format!(
+                    "Inbound UUID {} not in selected inbounds, skipping inbound injection for rule",
+                    inbound_uuid
+                  ),
                 );
               }
             }
@@ -924,7 +980,7 @@ async fn resolve_route(
 
   // Set default_domain_resolver if provided
   if let Some(dns_server_uuid) = &route.default_domain_resolver {
-    let dns_server = load_module_json("dns-server", dns_server_uuid).await?;
+    let dns_server = context.module_json("dns-server", dns_server_uuid)?;
     // Use tag if available, otherwise fall back to name
     let resolver_tag = if let Some(tag) = dns_server.get("tag").and_then(|t| t.as_str()) {
       tag.to_string()
@@ -945,8 +1001,11 @@ async fn resolve_route(
 }
 
 /// Resolve download_detour UUID to its tag (outbound tag or outbound_group name)
-async fn resolve_download_detour_tag(uuid: &str) -> Result<String, AppError> {
-  let tag = resolve_outbound_uuid_to_tag(uuid).await?;
+fn resolve_download_detour_tag(
+  context: &GenerationContext,
+  uuid: &str,
+) -> Result<String, AppError> {
+  let tag = context.outbound_tag(uuid)?;
   if tag.is_empty() {
     return Err(AppError::InternalServerError(
       "download_detour outbound has no tag".to_string(),
@@ -1,8 +1,14 @@
 #[cfg(test)]
 mod tests {
-  use super::super::config_generator::decode_base64_content;
-  use crate::backend::subscription_parser::parse_subscription_line;
+  use crate::backend::api::config_generator::{
+    SubscriptionOutbounds, dedup_outbounds, node_identity, read_subscriptions,
+  };
+  use crate::backend::api::generation_context::ParseCache;
+  use crate::backend::subscription_parser::{
+    decode_base64_content, parse_subscription, parse_subscription_line,
+  };
   use base64::Engine;
+  use serde_json::json;
 
   /// Create test subscription content as base64 (with proper padding)
   fn make_padded_base64(lines: &[&str]) -> String {
@@ -729,8 +735,187 @@ mod tests {
     let result2 = decode_base64_content(&encoded_url_safe);
 
     assert!(result1.is_some());
-    // Note: Our current implementation may not support URL-safe base64
-    // This test documents the expected behavior
-    let _ = result2; // May be Some or None depending on implementation
+    assert_eq!(
+      result1, result2,
+      "URL-safe base64 should decode to the same content"
+    );
+  }
+
+  // ========== Node deduplication ==========
+
+  fn subscription(uuid: &str, lines: &[&str]) -> SubscriptionOutbounds {
+    let outbounds = lines
+      .iter()
+      .map(|line| parse_subscription_line(line).unwrap())
+      .collect();
+    SubscriptionOutbounds {
+      uuid: uuid.to_string(),
+      name: uuid.to_string(),
+      priority: 0,
+      outbounds,
+    }
+  }
+
+  fn tags(subscriptions: &[SubscriptionOutbounds]) -> Vec<&str> {
+    subscriptions
+      .iter()
+      .flat_map(|s| &s.outbounds)
+      .map(|o| o["tag"].as_str().unwrap())
+      .collect()
+  }
+
+  #[test]
+  fn test_node_identity_ignores_tag_and_host_case() {
+    let a = parse_subscription_line("trojan://pass@HK.example.com:443#HK-A").unwrap();
+    let b = parse_subscription_line("trojan://pass@hk.example.com:443#Hong Kong").unwrap();
+    let other_password = parse_subscription_line("trojan://other@hk.example.com:443#HK").unwrap();
+    let other_port = parse_subscription_line("trojan://pass@hk.example.com:8443#HK").unwrap();
+
+    assert_eq!(node_identity(&a), node_identity(&b));
+    assert_ne!(node_identity(&a), node_identity(&other_password));
+    assert_ne!(node_identity(&a), node_identity(&other_port));
+  }
+
+  #[test]
+  fn test_dedup_keeps_first_subscription_by_default() {
+    let subscriptions = vec![
+      subscription(
+        "sub-a",
+        &[
+          "trojan://pass@hk.example.com:443#HK-A",
+          "trojan://pass@hk.example.com:443#HK-A-again",
+        ],
+      ),
+      subscription(
+        "sub-b",
+        &[
+          "trojan://pass@hk.example.com:443#HK-B",
+          "trojan://pass@jp.example.com:443#JP-B",
+        ],
+      ),
+    ];
+    let outbounds = dedup_outbounds(subscriptions, &[]);
+    assert_eq!(tags(&outbounds), vec!["HK-A", "JP-B"]);
+  }
+
+  #[test]
+  fn test_dedup_prefers_listed_subscription() {
+    let subscriptions = vec![
+      subscription(
+        "sub-a",
+        &[
+          "trojan://pass@hk.example.com:443#HK-A",
+          "trojan://pass@us.example.com:443#US-A",
+        ],
+      ),
+      subscription("sub-b", &["trojan://pass@hk.example.com:443#HK-B"]),
+    ];
+    let outbounds = dedup_outbounds(subscriptions, &["sub-b".to_string()]);
+    // The surviving nodes keep their original order
+    assert_eq!(tags(&outbounds), vec!["US-A", "HK-B"]);
+  }
+
+  #[test]
+  fn test_dedup_prefers_higher_priority() {
+    let mut preferred = subscription("sub-b", &["trojan://pass@hk.example.com:443#HK-B"]);
+    preferred.priority = 10;
+    let subscriptions = vec![
+      subscription("sub-a", &["trojan://pass@hk.example.com:443#HK-A"]),
+      preferred,
+    ];
+
+    let outbounds = dedup_outbounds(subscriptions, &[]);
+    assert_eq!(tags(&outbounds), vec!["HK-B"]);
+  }
+
+  #[test]
+  fn test_dedup_prefer_list_beats_priority() {
+    let mut high = subscription("sub-b", &["trojan://pass@hk.example.com:443#HK-B"]);
+    high.priority = 10;
+    let subscriptions = vec![
+      subscription("sub-a", &["trojan://pass@hk.example.com:443#HK-A"]),
+      high,
+    ];
+
+    let outbounds = dedup_outbounds(subscriptions, &["sub-a".to_string()]);
+    assert_eq!(tags(&outbounds), vec!["HK-A"]);
+  }
+
+  #[test]
+  fn test_parse_cache_follows_content_changes() {
+    let first = make_padded_base64(&["trojan://pw@a.example.com:443?security=tls#Node-A"]);
+    let second = make_padded_base64(&[
+      "trojan://pw@a.example.com:443?security=tls#Node-A",
+      "trojan://pw@b.example.com:443?security=tls#Node-B",
+    ]);
+    let mut cache = ParseCache::default();
+    let mut parses = 0;
+    let mut parse = |uuid: &str, content: &str| {
+      cache.parse_with(uuid, content, |content| {
+        parses += 1;
+        parse_subscription(content)
+      })
+    };
+
+    let parsed = parse("sub", &first);
+    assert_eq!(parsed.outbounds.len(), 1);
+
+    // Unchanged content is a cache hit
+    let cached = parse("sub", &first);
+    assert_eq!(cached.outbounds, parsed.outbounds);
+
+    let changed = parse("sub", &second);
+    assert_eq!(changed.outbounds.len(), 2);
+    assert_eq!(changed.outbounds[1]["tag"], "Node-B");
+
+    // Same content under another subscription is parsed separately
+    parse("other", &second);
+    assert_eq!(parses, 3);
+  }
+
+  #[test]
+  fn test_parse_cache_evicts_deleted_subscriptions() {
+    let content = make_padded_base64(&["trojan://pw@a.example.com:443?security=tls#Node-A"]);
+    let mut cache = ParseCache::default();
+    let mut parses = 0;
+    let mut parse_all = |cache: &mut ParseCache| {
+      for uuid in ["kept", "deleted", "removed-by-hand"] {
+        cache.parse_with(uuid, &content, |content| {
+          parses += 1;
+          parse_subscription(content)
+        });
+      }
+    };
+
+    parse_all(&mut cache);
+    cache.forget("deleted");
+    cache.retain(["kept", "deleted"]);
+    parse_all(&mut cache);
+    // Only "kept" was still cached
+    assert_eq!(parses, 5);
+  }
+
+  #[tokio::test]
+  async fn test_subscription_files_are_read_in_order() {
+    let dir = tempfile::tempdir().unwrap();
+    for uuid in ["first", "second", "unordered"] {
+      let subscribe =
+        json!({"uuid": uuid, "name": uuid, "json": json!({"content": ""}).to_string()});
+      std::fs::write(
+        dir.path().join(format!("{}.json", uuid)),
+        subscribe.to_string(),
+      )
+      .unwrap();
+    }
+    std::fs::write(
+      dir.path().join(".order.json"),
+      json!({"uuids": ["second", "first"]}).to_string(),
+    )
+    .unwrap();
+
+    // Every file appears once, ordered ones first, and the order file is not a subscription
+    let subscribes = read_subscriptions(dir.path()).await.unwrap();
+    let uuids: Vec<&str> = subscribes.iter().map(|s| s.uuid.as_str()).collect();
+    assert_eq!(uuids, vec!["second", "first", "unordered"]);
   }
 }
@@ -0,0 +1,290 @@
+use axum::{Json, extract::Path};
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::HashSet;
+
+use crate::backend::api::config::{ConfigCreateDto, RouteRuleDto};
+use crate::backend::api::config_generator::{build_config, load_config};
+use crate::backend::api::generation_context::{GenerationContext, OutboundKind};
+use crate::backend::error::AppError;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IssueKind {
+  /// A UUID that names no module
+  DanglingUuid,
+  /// A UUID found in more than one of outbounds, groups and filters
+  AmbiguousUuid,
+  /// Generation failed on a module that exists, e.g. invalid JSON
+  Generation,
+  EmptyGroup,
+  EmptyTag,
+  DuplicateTag,
+  UnselectedInbound,
+  UnknownRuleSet,
+  UnknownOutbound,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ValidationIssue {
+  pub kind: IssueKind,
+  pub message: String,
+}
+
+/// Result of `/api/config/{uuid}/validate`: errors would break the config, warnings are
+/// problems generation works around (and which a download silently applies)
+#[derive(Debug, Default, Serialize)]
+pub struct ValidationReport {
+  pub valid: bool,
+  pub errors: Vec<ValidationIssue>,
+  pub warnings: Vec<ValidationIssue>,
+}
+
+impl ValidationReport {
+  fn error(&mut self, kind: IssueKind, message: String) {
+    self.errors.push(ValidationIssue { kind, message });
+  }
+}
+
+/// Run a config's whole generation and report every problem found, without producing a download
+pub async fn validate_config(Path(uuid): Path<String>) -> Result<Json<ValidationReport>, AppError> {
+  let config = load_config(&uuid).await?;
+  let mut context = GenerationContext::load().await?;
+  Ok(Json(validate(&config, &mut context).await))
+}
+
+pub(crate) async fn validate(
+  config: &ConfigCreateDto,
+  context: &mut GenerationContext,
+) -> ValidationReport {
+  let mut report = ValidationReport::default();
+
+  // Generation stops at the first missing module, so collect them all up front
+  check_references(context, config, &mut report);
+  if report.errors.is_empty() {
+    match build_config(config, context).await {
+      Ok(generated) => check_generated(&generated, &mut report),
+      Err(e) => {
+        let rejected = context.take_errors();
+        if rejected.is_empty() {
+          report.error(IssueKind::Generation, error_message(e));
+        } else {
+          report.errors.extend(rejected);
+        }
+      }
+    }
+  }
+
+  report.warnings.extend(context.take_warnings());
+  report.valid = report.errors.is_empty();
+  report
+}
+
+fn error_message(err: AppError) -> String {
+  match err {
+    AppError::BadRequest(msg)
+    | AppError::NotFound(msg)
+    | AppError::BadGateway(msg)
+    | AppError::InternalServerError(msg) => msg,
+    AppError::AnyhowError(err) => err.to_string(),
+  }
+}
+
+/// Report every UUID the config (and the groups it reaches) uses that names no module
+fn check_references(
+  context: &GenerationContext,
+  config: &ConfigCreateDto,
+  report: &mut ValidationReport,
+) {
+  let mut module = |module_type: &str, uuid: &str, field: &str| {
+    if !context.has_module(module_type, uuid) {
+      report.error(
+        IssueKind::DanglingUuid,
+        format!(
+          "{} references missing {} module {}",
+          field, module_type, uuid
+        ),
+      );
+    }
+  };
+
+  module("logs", &config.log, "log");
+  module("experimentals", &config.experimental, "experimental");
+  if let Some(uuid) = &config.dns.config {
+    module("dns-config", uuid, "dns.config");
+  }
+  for server in &config.dns.servers {
+    module("dns-server", &server.uuid, "dns.servers");
+  }
+  for rule in config.dns.rules.iter().flatten() {
+    for ruleset in &rule.rule_set {
+      module("rulesets", ruleset, "dns.rules");
+    }
+    module("dns-server", &rule.server, "dns.rules");
+  }
+  module("dns-server", &config.dns.final_server, "dns.final");
+  for inbound in &config.inbounds {
+    module("inbounds", inbound, "inbounds");
+  }
+  if let Some(uuid) = &config.route.config {
+    module("routes", uuid, "route.config");
+  }
+  if let Some(uuid) = &config.route.default_domain_resolver {
+    module("dns-server", uuid, "route.default_domain_resolver");
+  }
+
+  let mut outbounds = vec![
+    (
+      config.route.final_outbound.clone(),
+      "route.final".to_string(),
+    ),
+    (
+      config.ext_config.download_detour.clone(),
+      "ext_config.download_detour".to_string(),
+    ),
+  ];
+  outbounds.extend(
+    config
+      .dns
+      .servers
+      .iter()
+      .filter_map(|s| s.detour.clone())
+      .map(|uuid| (uuid, "dns.servers detour".to_string())),
+  );
+  for rule in config.route.rules.iter().flatten() {
+    match rule {
+      RouteRuleDto::Ruleset { rulesets, outbound } => {
+        for ruleset in rulesets {
+          module("rulesets", ruleset, "route.rules");
+        }
+        outbounds.push((outbound.clone(), "route.rules".to_string()));
+      }
+      RouteRuleDto::Rule {
+        rule,
+        outbound,
+        inbound,
+      } => {
+        module("rules", rule, "route.rules");
+        if let Some(inbound) = inbound {
+          module("inbounds", inbound, "route.rules inbound");
+        }
+        if let Some(outbound) = outbound {
+          outbounds.push((outbound.clone(), "route.rules".to_string()));
+        }
+      }
+    }
+  }
+
+  // Follow groups into their members
+  let mut visited = HashSet::new();
+  while let Some((uuid, field)) = outbounds.pop() {
+    if !visited.insert((uuid.clone(), field.clone())) {
+      continue;
+    }
+    match context.outbound_kind(&uuid) {
+      Ok(OutboundKind::Group) => {
+        if let Ok(group) = context.group(&uuid) {
+          let field = format!("outbound group {}", group.name);
+          outbounds.extend(group.outbounds.into_iter().map(|m| (m, field.clone())));
+        }
+      }
+      Ok(_) => {}
+      Err(AppError::NotFound(_)) => report.error(
+        IssueKind::DanglingUuid,
+        format!("{} references missing outbound {}", field, uuid),
+      ),
+      Err(e) => report.error(IssueKind::AmbiguousUuid, error_message(e)),
+    }
+  }
+}
+
+fn tags(values: Option<&Value>) -> Vec<&str> {
+  values
+    .and_then(|v| v.as_array())
+    .map(|items| {
+      items
+        .iter()
+        .filter_map(|item| item.get("tag").and_then(|t| t.as_str()))
+        .collect()
+    })
+    .unwrap_or_default()
+}
+
+/// Tags a rule refers to through a string-or-array field such as "rule_set"
+fn rule_refs<'a>(rule: &'a Value, field: &str) -> Vec<&'a str> {
+  match rule.get(field) {
+    Some(Value::String(tag)) => vec![tag.as_str()],
+    Some(Value::Array(tags)) => tags.iter().filter_map(|t| t.as_str()).collect(),
+    _ => Vec::new(),
+  }
+}
+
+/// Check the tag references of a generated sing-box config
+pub(crate) fn check_generated(config: &Value, report: &mut ValidationReport) {
+  let sections = [
+    ("outbound", config.get("outbounds")),
+    ("inbound", config.get("inbounds")),
+    ("DNS server", config.pointer("/dns/servers")),
+    ("rule_set", config.pointer("/route/rule_set")),
+  ];
+  for (section, values) in sections {
+    let mut seen = HashSet::new();
+    for tag in tags(values) {
+      if !seen.insert(tag) {
+        report.error(
+          IssueKind::DuplicateTag,
+          format!("Duplicate {} tag: {}", section, tag),
+        );
+      }
+    }
+  }
+
+  let rule_sets: HashSet<&str> = tags(config.pointer("/route/rule_set"))
+    .into_iter()
+    .collect();
+  let outbounds: HashSet<&str> = tags(config.get("outbounds")).into_iter().collect();
+  let rules = [
+    ("route", config.pointer("/route/rules")),
+    ("DNS", config.pointer("/dns/rules")),
+  ];
+  for (section, rules) in rules {
+    for rule in rules.and_then(|r| r.as_array()).into_iter().flatten() {
+      for tag in rule_refs(rule, "rule_set") {
+        if !rule_sets.contains(tag) {
+          report.error(
+            IssueKind::UnknownRuleSet,
+            format!(
+              "A {} rule uses rule_set {}, which is not defined",
+              section, tag
+            ),
+          );
+        }
+      }
+    }
+  }
+
+  for rule in config
+    .pointer("/route/rules")
+    .and_then(|r| r.as_array())
+    .into_iter()
+    .flatten()
+  {
+    if let Some(tag) = rule.get("outbound").and_then(|t| t.as_str())
+      && !outbounds.contains(tag)
+    {
+      report.error(
+        IssueKind::UnknownOutbound,
+        format!("A route rule uses outbound {}, which is not defined", tag),
+      );
+    }
+  }
+  if let Some(tag) = config.pointer("/route/final").and_then(|t| t.as_str())
+    && !tag.is_empty()
+    && !outbounds.contains(tag)
+  {
+    report.error(
+      IssueKind::UnknownOutbound,
+      format!("route.final uses outbound {}, which is not defined", tag),
+    );
+  }
+}
@@ -0,0 +1,245 @@
+#[cfg(test)]
+mod tests {
+  use super::super::config::ConfigCreateDto;
+  use super::super::config_generator::build_config;
+  use super::super::config_validation::{IssueKind, ValidationReport, check_generated, validate};
+  use super::super::generation_context::{GenerationContext, test_module};
+  use super::super::subscribe::SubscribeCreateDto;
+  use crate::backend::error::AppError;
+  use serde_json::json;
+
+  fn context() -> GenerationContext {
+    GenerationContext::fixture()
+  }
+
+  fn config(route_rules: serde_json::Value) -> ConfigCreateDto {
+    serde_json::from_value(json!({
+      "uuid": "config",
+      "name": "Config",
+      "log": "log",
+      "dns": {"config": null, "servers": [{"uuid": "dns", "detour": null}], "rules": null, "final": "dns"},
+      "inbounds": ["mixed"],
+      "route": {"config": null, "rules": route_rules, "final": "direct", "default_domain_resolver": null},
+      "experimental": "exp",
+      "ext_config": {"download_detour": "direct"}
+    }))
+    .unwrap()
+  }
+
+  fn kinds(issues: &[super::super::config_validation::ValidationIssue]) -> Vec<IssueKind> {
+    issues.iter().map(|issue| issue.kind).collect()
+  }
+
+  #[tokio::test]
+  async fn test_validate_reports_every_dangling_uuid() {
+    let mut config = config(json!([
+      {"type": "ruleset", "rulesets": ["missing-ruleset"], "outbound": "missing-outbound"}
+    ]));
+    config.log = "missing-log".to_string();
+
+    let report = validate(&config, &mut context()).await;
+    assert!(!report.valid);
+    assert_eq!(
+      kinds(&report.errors),
+      vec![IssueKind::DanglingUuid; 3],
+      "{:?}",
+      report.errors
+    );
+  }
+
+  #[tokio::test]
+  async fn test_validate_collects_generation_warnings() {
+    let config = config(json!([
+      {"type": "rule", "rule": "rule", "outbound": "empty", "inbound": "other"}
+    ]));
+    let mut context = context();
+    context.insert_module(
+      "inbounds",
+      "other",
+      test_module("Other", json!({"type": "mixed", "tag": "other-in"})),
+    );
+
+    let report = validate(&config, &mut context).await;
+    // The rule module refers to a rule_set that is never defined, and its outbound group is
+    // dropped for being empty
+    assert_eq!(
+      kinds(&report.errors),
+      vec![IssueKind::UnknownRuleSet, IssueKind::UnknownOutbound]
+    );
+    let warnings = kinds(&report.warnings);
+    assert!(warnings.contains(&IssueKind::EmptyGroup));
+    assert!(warnings.contains(&IssueKind::UnselectedInbound));
+  }
+
+  #[tokio::test]
+  async fn test_validate_clean_config() {
+    let report = validate(&config(json!([])), &mut context()).await;
+    assert!(report.valid, "{:?}", report.errors);
+    assert!(report.warnings.is_empty());
+  }
+
+  #[test]
+  fn test_check_generated() {
+    let generated = json!({
+      "dns": {
+        "servers": [{"tag": "local"}, {"tag": "local"}],
+        "rules": [{"rule_set": ["geosite-cn"], "server": "local"}]
+      },
+      "inbounds": [{"tag": "mixed-in"}],
+      "outbounds": [{"tag": "direct"}],
+      "route": {
+        "rule_set": [{"tag": "geoip-cn"}],
+        "rules": [{"rule_set": ["geoip-cn"], "outbound": "proxy"}],
+        "final": "direct"
+      }
+    });
+    let mut report = ValidationReport::default();
+    check_generated(&generated, &mut report);
+    assert_eq!(
+      kinds(&report.errors),
+      vec![
+        IssueKind::DuplicateTag,
+        IssueKind::UnknownRuleSet,
+        IssueKind::UnknownOutbound
+      ]
+    );
+  }
+
+  #[tokio::test]
+  async fn test_generation_rejects_tag_collisions() {
+    let colliding = || {
+      let mut context = context();
+      context.insert_module(
+        "outbounds",
+        "proxy",
+        test_module("Proxy", json!({"type": "socks", "tag": "Shared"})),
+      );
+      context.insert_module(
+        "outbound-group",
+        "shared",
+        json!({"uuid": "shared", "name": "Shared", "group_type": "selector", "outbounds": ["direct", "proxy"]})
+          .to_string(),
+      );
+      context
+    };
+    let mut config = config(json!([]));
+    config.route.final_outbound = "shared".to_string();
+
+    let err = build_config(&config, &mut colliding()).await.unwrap_err();
+    match err {
+      AppError::BadRequest(message) => {
+        assert!(message.contains("outbound group shared"), "{}", message);
+        assert!(message.contains("outbound proxy"), "{}", message);
+      }
+      other => panic!("unexpected error: {:?}", other),
+    }
+
+    let report = validate(&config, &mut colliding()).await;
+    assert_eq!(kinds(&report.errors), vec![IssueKind::DuplicateTag]);
+  }
+
+  #[tokio::test]
+  async fn test_generation_rejects_duplicate_inbound_and_dns_tags() {
+    let mut context = context();
+    context.insert_module(
+      "inbounds",
+      "other",
+      test_module("Other", json!({"type": "socks", "tag": "mixed-in"})),
+    );
+    let mut inbound_config = config(json!([]));
+    inbound_config.inbounds.push("other".to_string());
+    let report = validate(&inbound_config, &mut context).await;
+    assert_eq!(kinds(&report.errors), vec![IssueKind::DuplicateTag]);
+
+    context.insert_module(
+      "dns-server",
+      "remote",
+      test_module("Remote", json!({"type": "udp", "tag": "local"})),
+    );
+    let mut config = config(json!([]));
+    config
+      .dns
+      .servers
+      .push(serde_json::from_value(json!({"uuid": "remote", "detour": null})).unwrap());
+    let report = validate(&config, &mut context).await;
+    assert_eq!(kinds(&report.errors), vec![IssueKind::DuplicateTag]);
+  }
+
+  #[tokio::test]
+  async fn test_outbound_shared_by_groups_is_not_a_collision() {
+    let mut context = context();
+    for (uuid, name) in [("a", "A"), ("b", "B"), ("top", "Top")] {
+      let members = if uuid == "top" {
+        json!(["a", "b"])
+      } else {
+        json!(["direct"])
+      };
+      context.insert_module(
+        "outbound-group",
+        uuid,
+        json!({"uuid": uuid, "name": name, "group_type": "selector", "outbounds": members})
+          .to_string(),
+      );
+    }
+    let mut config = config(json!([]));
+    config.route.final_outbound = "top".to_string();
+    let report = validate(&config, &mut context).await;
+    assert!(report.valid, "{:?}", report.errors);
+  }
+
+  #[tokio::test]
+  async fn test_generation_rejects_node_named_like_a_group() {
+    let mut context = context();
+    context.insert_subscription(SubscribeCreateDto {
+      uuid: "sub".to_string(),
+      name: "Sub".to_string(),
+      json: json!({
+        "source": "inline",
+        "tag_template": "{name}",
+        "content": "trojan://pw@hk.example.com:443?security=tls#Shared"
+      })
+      .to_string(),
+    });
+    context.insert_module(
+      "filters",
+      "nodes",
+      json!({"uuid": "nodes", "name": "Nodes", "filter_type": "simple", "pattern": "Shared"})
+        .to_string(),
+    );
+    context.insert_module(
+      "outbound-group",
+      "shared",
+      json!({"uuid": "shared", "name": "Shared", "group_type": "selector", "outbounds": ["nodes"]})
+        .to_string(),
+    );
+    let mut config = config(json!([]));
+    config.route.final_outbound = "shared".to_string();
+
+    let report = validate(&config, &mut context).await;
+    assert_eq!(
+      kinds(&report.errors),
+      vec![IssueKind::DuplicateTag],
+      "{:?}",
+      report.errors
+    );
+    assert!(report.errors[0].message.contains("outbound group shared"));
+    assert!(report.errors[0].message.contains("subscription node"));
+  }
+
+  #[tokio::test]
+  async fn test_inbound_and_dns_server_selected_twice_are_emitted_once() {
+    let mut config = config(json!([]));
+    config.inbounds.push("mixed".to_string());
+    config
+      .dns
+      .servers
+      .push(serde_json::from_value(json!({"uuid": "dns", "detour": null})).unwrap());
+
+    let generated = build_config(&config, &mut context()).await.unwrap();
+    assert_eq!(generated["inbounds"].as_array().unwrap().len(), 1);
+    assert_eq!(generated["dns"]["servers"].as_array().unwrap().len(), 1);
+
+    let report = validate(&config, &mut context()).await;
+    assert!(report.valid, "{:?}", report.errors);
+  }
+}
@@ -1,18 +1,280 @@
 use axum::{Json, http::StatusCode, response::IntoResponse};
+use regex::{Regex, RegexBuilder};
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 use std::path::Path;
 use tokio::fs;
 
+use crate::backend::api::config::DedupConfigDto;
+use crate::backend::api::config_generator::{
+  dedup_subscriptions, filter_outbounds, filter_subscriptions, get_subscription_outbounds,
+};
 use crate::backend::error::AppError;
+use crate::backend::node_filter::{FilterConditions, natural_cmp};
+
+/// Matching settings shared by the filter create, update, list and preview payloads
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct FilterSpec {
+  pub filter_type: String, // "simple" or "regex"
+  pub pattern: String,
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub except: Option<String>, // Optional except pattern, same syntax as `pattern`
+  /// Ignore letter case in `pattern` and `except`
+  #[serde(default)]
+  pub case_insensitive: bool,
+  /// Only match whole words, so "US" does not match "AUS"
+  #[serde(default)]
+  pub whole_word: bool,
+  /// Node conditions (protocol, server, port, ...) combined with the tag pattern
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub conditions: Option<FilterConditions>,
+  #[serde(default)]
+  pub sort: FilterSort,
+  /// Keep only the first N nodes after sorting
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub limit: Option<usize>,
+  /// Subscription uuids the filter draws nodes from; empty means every subscription.
+  #[serde(default, skip_serializing_if = "Vec::is_empty")]
+  pub subscriptions: Vec<String>,
+}
+
+impl Default for FilterSpec {
+  fn default() -> Self {
+    Self {
+      filter_type: "simple".to_string(),
+      pattern: String::new(),
+      except: None,
+      case_insensitive: false,
+      whole_word: false,
+      conditions: None,
+      sort: FilterSort::default(),
+      limit: None,
+      subscriptions: Vec::new(),
+    }
+  }
+}
 
 #[derive(Debug, Deserialize, Serialize)]
 pub struct FilterCreateDto {
   pub uuid: String,
   pub name: String,
-  pub filter_type: String, // "simple" or "regex"
-  pub pattern: String,
-  #[serde(skip_serializing_if = "Option::is_none")]
-  pub except: Option<String>, // Optional except pattern (only for "simple" type)
+  #[serde(flatten)]
+  pub spec: FilterSpec,
+}
+
+/// Order of the nodes a filter emits
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FilterSort {
+  /// Subscription order, then node order within each subscription
+  #[default]
+  Subscription,
+  /// Alphabetical by tag
+  Name,
+  /// By tag, comparing digit runs as numbers ("HK 2" before "HK 10")
+  Natural,
+}
+
+/// Outcome of running a filter against one node
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FilterMatch {
+  Matched,
+  /// Matched the pattern but removed by `except`
+  Excluded,
+  Unmatched,
+}
+
+/// Filter with its patterns compiled, ready to run against many nodes
+#[derive(Debug)]
+pub struct CompiledFilter {
+  pub name: String,
+  /// False when the pattern is empty, leaving the selection to the conditions
+  has_pattern: bool,
+  pattern: Regex,
+  except: Option<Regex>,
+  conditions: Option<FilterConditions>,
+  sort: FilterSort,
+  limit: Option<usize>,
+  subscriptions: Vec<String>,
+}
+
+impl FilterCreateDto {
+  /// Compile the pattern and except pattern, rejecting invalid ones
+  pub fn compile(&self) -> Result<CompiledFilter, AppError> {
+    if let Some(conditions) = &self.spec.conditions {
+      conditions.validate()?;
+    }
+    if self.spec.limit == Some(0) {
+      return Err(AppError::BadRequest(
+        "Filter limit must be at least 1".to_string(),
+      ));
+    }
+
+    let pattern = self.compile_pattern(&self.spec.pattern, "pattern")?;
+    let except = match self.spec.except.as_deref().map(str::trim) {
+      Some(except) if !except.is_empty() => Some(self.compile_pattern(except, "except")?),
+      _ => None,
+    };
+
+    Ok(CompiledFilter {
+      name: self.name.clone(),
+      has_pattern: !self.spec.pattern.trim().is_empty(),
+      pattern,
+      except,
+      conditions: self.spec.conditions.clone(),
+      sort: self.spec.sort,
+      limit: self.spec.limit,
+      subscriptions: self.spec.subscriptions.clone(),
+    })
+  }
+
+  fn compile_pattern(&self, pattern: &str, field: &str) -> Result<Regex, AppError> {
+    let source = match self.spec.filter_type.as_str() {
+      // Simple contains match for any `|`-separated part
+      "simple" => pattern
+        .split('|')
+        .map(|part| regex::escape(part.trim()))
+        .collect::<Vec<_>>()
+        .join("|"),
+      "regex" => pattern.to_string(),
+      other => {
+        return Err(AppError::BadRequest(format!(
+          "Unknown filter type: {}",
+          other
+        )));
+      }
+    };
+    let source = if self.spec.whole_word {
+      format!(r"\b(?:{})\b", source)
+    } else {
+      source
+    };
+
+    RegexBuilder::new(&source)
+      .case_insensitive(self.spec.case_insensitive)
+      .build()
+      .map_err(|e| {
+        let message = format!("Invalid filter {}: {}", field, e);
+        AppError::BadRequest(if self.name.is_empty() {
+          message
+        } else {
+          format!("{} ({})", message, self.name)
+        })
+      })
+  }
+}
+
+impl CompiledFilter {
+  /// Check whether a subscription outbound passes this filter
+  pub fn matches(&self, outbound: &Value, subscription_uuid: &str) -> bool {
+    self.match_outbound(outbound, subscription_uuid) == FilterMatch::Matched
+  }
+
+  /// Whether the filter takes nodes from this subscription
+  pub fn draws_from(&self, subscription_uuid: &str) -> bool {
+    self.subscriptions.is_empty() || self.subscriptions.iter().any(|s| s == subscription_uuid)
+  }
+
+  /// Classify a subscription outbound against the tag pattern, the conditions and `except`
+  pub fn match_outbound(&self, outbound: &Value, subscription_uuid: &str) -> FilterMatch {
+    if !self.draws_from(subscription_uuid) {
+      return FilterMatch::Unmatched;
+    }
+    let tag = outbound
+      .get("tag")
+      .and_then(|t| t.as_str())
+      .unwrap_or_default();
+    let Some(conditions) = &self.conditions else {
+      return self.match_tag(tag);
+    };
+
+    let tag_match = self.has_pattern.then(|| self.pattern.is_match(tag));
+    if !conditions.combine(tag_match, outbound, subscription_uuid) {
+      FilterMatch::Unmatched
+    } else if self.except_matches(tag) {
+      FilterMatch::Excluded
+    } else {
+      FilterMatch::Matched
+    }
+  }
+
+  /// Classify a node tag against the pattern and the except pattern
+  pub fn match_tag(&self, tag: &str) -> FilterMatch {
+    if !self.pattern.is_match(tag) {
+      FilterMatch::Unmatched
+    } else if self.except_matches(tag) {
+      FilterMatch::Excluded
+    } else {
+      FilterMatch::Matched
+    }
+  }
+
+  /// Sort the matched nodes and cut them to the limit
+  pub fn arrange(&self, outbounds: &mut Vec<Value>) {
+    let tag = |outbound: &Value| {
+      outbound
+        .get("tag")
+        .and_then(|t| t.as_str())
+        .unwrap_or_default()
+        .to_string()
+    };
+    match self.sort {
+      FilterSort::Subscription => {}
+      FilterSort::Name => outbounds.sort_by_cached_key(tag),
+      FilterSort::Natural => outbounds.sort_by(|a, b| natural_cmp(&tag(a), &tag(b))),
+    }
+    if let Some(limit) = self.limit {
+      outbounds.truncate(limit);
+    }
+  }
+
+  fn except_matches(&self, tag: &str) -> bool {
+    self
+      .except
+      .as_ref()
+      .is_some_and(|except| except.is_match(tag))
+  }
+}
+
+/// Reject filters scoped to subscriptions that do not exist
+async fn check_subscriptions_exist(uuids: &[String]) -> Result<(), AppError> {
+  let dir_path = Path::new("./data/subscribes");
+  if let Some(uuid) = uuids
+    .iter()
+    .find(|uuid| !dir_path.join(format!("{}.json", uuid)).exists())
+  {
+    return Err(AppError::BadRequest(format!(
+      "Subscription not found: {}",
+      uuid
+    )));
+  }
+  Ok(())
+}
+
+/// Names of the filters in `dir_path` scoped to the given subscription
+pub(crate) async fn filters_using_subscription(
+  dir_path: &Path,
+  uuid: &str,
+) -> Result<Vec<String>, AppError> {
+  if !dir_path.exists() {
+    return Ok(Vec::new());
+  }
+
+  let mut entries = fs::read_dir(dir_path).await?;
+  let mut names = Vec::new();
+  while let Some(entry) = entries.next_entry().await? {
+    let path = entry.path();
+    if path.extension().and_then(|s| s.to_str()) == Some("json") {
+      let content = fs::read_to_string(&path).await?;
+      if let Ok(filter) = serde_json::from_str::<FilterCreateDto>(&content)
+        && filter.spec.subscriptions.iter().any(|s| s == uuid)
+      {
+        names.push(filter.name);
+      }
+    }
+  }
+  names.sort();
+  Ok(names)
 }
 
 pub async fn create_filter(
@@ -20,6 +282,8 @@ pub async fn create_filter(
 ) -> Result<impl IntoResponse, AppError> {
   let file_name = format!("{}.json", payload.uuid);
   log::info!("Creating filter: {}", file_name);
+  payload.compile()?;
+  check_subscriptions_exist(&payload.spec.subscriptions).await?;
   let dir_path = Path::new("./data/filters");
   let file_path = dir_path.join(&file_name);
 
@@ -40,10 +304,8 @@ pub async fn create_filter(
 pub struct FilterListDto {
   pub uuid: String,
   pub name: String,
-  pub filter_type: String,
-  pub pattern: String,
-  #[serde(skip_serializing_if = "Option::is_none")]
-  pub except: Option<String>,
+  #[serde(flatten)]
+  pub spec: FilterSpec,
 }
 
 pub async fn list_filters() -> Result<impl IntoResponse, AppError> {
@@ -63,9 +325,7 @@ pub async fn list_filters() -> Result<impl IntoResponse, AppError> {
         filters.push(FilterListDto {
           uuid: filter_dto.uuid,
           name: filter_dto.name,
-          filter_type: filter_dto.filter_type,
-          pattern: filter_dto.pattern,
-          except: filter_dto.except,
+          spec: filter_dto.spec,
         });
       }
     }
@@ -78,10 +338,8 @@ pub async fn list_filters() -> Result<impl IntoResponse, AppError> {
 pub struct FilterUpdateDto {
   pub uuid: String,
   pub name: String,
-  pub filter_type: String,
-  pub pattern: String,
-  #[serde(skip_serializing_if = "Option::is_none")]
-  pub except: Option<String>,
+  #[serde(flatten)]
+  pub spec: FilterSpec,
 }
 
 pub async fn update_filter(
@@ -99,10 +357,10 @@ pub async fn update_filter(
   let storage_dto = FilterCreateDto {
     uuid: payload.uuid,
     name: payload.name,
-    filter_type: payload.filter_type,
-    pattern: payload.pattern,
-    except: payload.except,
+    spec: payload.spec,
   };
+  storage_dto.compile()?;
+  check_subscriptions_exist(&storage_dto.spec.subscriptions).await?;
 
   fs::write(file_path, serde_json::to_string(&storage_dto)?.as_bytes()).await?;
 
@@ -129,3 +387,78 @@ pub async fn delete_filter(
 
   Ok((StatusCode::OK, "Filter deleted successfully").into_response())
 }
+
+/// Unsaved filter to try against the current subscriptions
+#[derive(Debug, Deserialize)]
+pub struct FilterPreviewDto {
+  #[serde(flatten)]
+  pub spec: FilterSpec,
+  /// Node deduplication to apply first, as in the config being built
+  pub dedup: Option<DedupConfigDto>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SubscriptionFilterPreviewDto {
+  pub uuid: String,
+  pub name: String,
+  pub matched: Vec<String>,
+  /// Tags that matched the pattern but were removed by `except`
+  pub excluded: Vec<String>,
+  pub unmatched: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct FilterPreviewResultDto {
+  /// Tags the filter would emit, in generation order
+  pub matched: Vec<String>,
+  pub subscriptions: Vec<SubscriptionFilterPreviewDto>,
+}
+
+/// Dry-run a filter against every enabled subscription without saving it
+pub async fn preview_filter(
+  Json(payload): Json<FilterPreviewDto>,
+) -> Result<impl IntoResponse, AppError> {
+  let filter = FilterCreateDto {
+    uuid: String::new(),
+    name: String::new(),
+    spec: payload.spec,
+  }
+  .compile()?;
+  let subscriptions =
+    dedup_subscriptions(get_subscription_outbounds().await?, payload.dedup.as_ref());
+
+  let mut previews = Vec::new();
+  for subscription in filter_subscriptions(&filter, &subscriptions) {
+    let mut preview = SubscriptionFilterPreviewDto {
+      uuid: subscription.uuid.clone(),
+      name: subscription.name.clone(),
+      matched: Vec::new(),
+      excluded: Vec::new(),
+      unmatched: Vec::new(),
+    };
+    for outbound in &subscription.outbounds {
+      let Some(tag) = outbound.get("tag").and_then(|t| t.as_str()) else {
+        continue;
+      };
+      let bucket = match filter.match_outbound(outbound, &subscription.uuid) {
+        FilterMatch::Matched => &mut preview.matched,
+        FilterMatch::Excluded => &mut preview.excluded,
+        FilterMatch::Unmatched => &mut preview.unmatched,
+      };
+      bucket.push(tag.to_string());
+    }
+    previews.push(preview);
+  }
+
+  // Same selection the config generator makes for this filter
+  let matched = filter_outbounds(&filter, &subscriptions)
+    .iter()
+    .filter_map(|o| o.get("tag").and_then(|t| t.as_str()))
+    .map(str::to_string)
+    .collect();
+
+  Ok(Json(FilterPreviewResultDto {
+    matched,
+    subscriptions: previews,
+  }))
+}
@@ -0,0 +1,293 @@
+#[cfg(test)]
+mod tests {
+  use super::super::config::DedupConfigDto;
+  use super::super::config_generator::{
+    SubscriptionOutbounds, dedup_subscriptions, filter_outbounds, filter_subscriptions,
+  };
+  use super::super::filter::{
+    FilterCreateDto, FilterMatch, FilterSort, FilterSpec, filters_using_subscription,
+  };
+  use serde_json::json;
+
+  fn filter(filter_type: &str, pattern: &str, except: Option<&str>) -> FilterCreateDto {
+    FilterCreateDto {
+      uuid: String::new(),
+      name: String::new(),
+      spec: FilterSpec {
+        filter_type: filter_type.to_string(),
+        pattern: pattern.to_string(),
+        except: except.map(str::to_string),
+        ..Default::default()
+      },
+    }
+  }
+
+  fn subscription(uuid: &str, tags: &[&str]) -> SubscriptionOutbounds {
+    SubscriptionOutbounds {
+      uuid: uuid.to_string(),
+      name: uuid.to_string(),
+      priority: 0,
+      outbounds: tags
+        .iter()
+        .map(
+          |tag| json!({"type": "trojan", "tag": tag, "server": "example.com", "server_port": 443}),
+        )
+        .collect(),
+    }
+  }
+
+  #[test]
+  fn test_match_tag_simple_with_except() {
+    let f = filter("simple", "HK|香港", Some("IPLC")).compile().unwrap();
+    assert_eq!(f.match_tag("HK-01"), FilterMatch::Matched);
+    assert_eq!(f.match_tag("香港 02"), FilterMatch::Matched);
+    assert_eq!(f.match_tag("HK IPLC"), FilterMatch::Excluded);
+    assert_eq!(f.match_tag("JP-01"), FilterMatch::Unmatched);
+  }
+
+  #[test]
+  fn test_match_tag_regex() {
+    let f = filter("regex", r"^(HK|JP)-\d+$", None).compile().unwrap();
+    assert_eq!(f.match_tag("HK-01"), FilterMatch::Matched);
+    assert_eq!(f.match_tag("US-01"), FilterMatch::Unmatched);
+  }
+
+  #[test]
+  fn test_filter_outbounds_keeps_subscription_order() {
+    let subscriptions = vec![
+      subscription("a", &["HK-A1", "JP-A1", "HK-A2"]),
+      subscription("b", &["HK-B1"]),
+    ];
+    let tags: Vec<String> = filter_outbounds(
+      &filter("simple", "HK", None).compile().unwrap(),
+      &subscriptions,
+    )
+    .iter()
+    .map(|o| o["tag"].as_str().unwrap().to_string())
+    .collect();
+    assert_eq!(tags, vec!["HK-A1", "HK-A2", "HK-B1"]);
+  }
+
+  #[test]
+  fn test_filter_conditions_with_pattern() {
+    let mut f = filter("simple", "", Some("IPLC"));
+    f.spec.conditions = Some(
+      serde_json::from_value(json!({
+        "rules": [
+          {"field": "reality", "enabled": true},
+          {"field": "subscription", "values": ["a"]},
+          {"field": "port", "values": ["443"]}
+        ]
+      }))
+      .unwrap(),
+    );
+    let reality = |tag: &str, port: u64| {
+      json!({
+        "type": "vless",
+        "tag": tag,
+        "server": "example.com",
+        "server_port": port,
+        "tls": {"enabled": true, "reality": {"enabled": true}}
+      })
+    };
+
+    let compiled = f.compile().unwrap();
+    assert_eq!(
+      compiled.match_outbound(&reality("HK-01", 443), "a"),
+      FilterMatch::Matched
+    );
+    assert_eq!(
+      compiled.match_outbound(&reality("HK IPLC", 443), "a"),
+      FilterMatch::Excluded
+    );
+    assert_eq!(
+      compiled.match_outbound(&reality("HK-01", 8443), "a"),
+      FilterMatch::Unmatched
+    );
+    assert_eq!(
+      compiled.match_outbound(&reality("HK-01", 443), "b"),
+      FilterMatch::Unmatched
+    );
+
+    // A non-empty pattern joins the conditions
+    f.spec.pattern = "JP".to_string();
+    let compiled = f.compile().unwrap();
+    assert_eq!(
+      compiled.match_outbound(&reality("HK-01", 443), "a"),
+      FilterMatch::Unmatched
+    );
+    assert_eq!(
+      compiled.match_outbound(&reality("JP-01", 443), "a"),
+      FilterMatch::Matched
+    );
+  }
+
+  #[test]
+  fn test_filter_outbounds_by_subscription_condition() {
+    let mut f = filter("simple", "HK", None);
+    f.spec.conditions = Some(
+      serde_json::from_value(json!({"rules": [{"field": "subscription", "values": ["b"]}]}))
+        .unwrap(),
+    );
+    let subscriptions = vec![
+      subscription("a", &["HK-A1"]),
+      subscription("b", &["HK-B1", "JP-B1"]),
+    ];
+    let tags: Vec<String> = filter_outbounds(&f.compile().unwrap(), &subscriptions)
+      .iter()
+      .map(|o| o["tag"].as_str().unwrap().to_string())
+      .collect();
+    assert_eq!(tags, vec!["HK-B1"]);
+  }
+
+  #[test]
+  fn test_regex_except_and_case_insensitive() {
+    let mut f = filter("regex", r"^(hk|jp)", Some(r"iplc|\d{3}"));
+    f.spec.case_insensitive = true;
+    let f = f.compile().unwrap();
+    assert_eq!(f.match_tag("HK-01"), FilterMatch::Matched);
+    assert_eq!(f.match_tag("jp-02"), FilterMatch::Matched);
+    assert_eq!(f.match_tag("HK IPLC"), FilterMatch::Excluded);
+    assert_eq!(f.match_tag("HK-100"), FilterMatch::Excluded);
+    assert_eq!(f.match_tag("US-01"), FilterMatch::Unmatched);
+  }
+
+  #[test]
+  fn test_simple_whole_word() {
+    let mut f = filter("simple", "US|a.b", None);
+    f.spec.whole_word = true;
+    let f = f.compile().unwrap();
+    assert_eq!(f.match_tag("US 01"), FilterMatch::Matched);
+    assert_eq!(f.match_tag("AUS 01"), FilterMatch::Unmatched);
+    assert_eq!(f.match_tag("USA"), FilterMatch::Unmatched);
+    // Simple patterns are literal text
+    assert_eq!(f.match_tag("a.b"), FilterMatch::Matched);
+    assert_eq!(f.match_tag("axb"), FilterMatch::Unmatched);
+  }
+
+  #[test]
+  fn test_compile_rejects_invalid_patterns() {
+    assert!(filter("regex", "(HK", None).compile().is_err());
+    assert!(filter("regex", "HK", Some("[")).compile().is_err());
+    assert!(filter("simple", "(HK", None).compile().is_ok());
+    assert!(filter("glob", "HK*", None).compile().is_err());
+  }
+
+  #[test]
+  fn test_filter_outbounds_sort_and_limit() {
+    let subscriptions = || {
+      vec![
+        subscription("a", &["US 10", "US 2"]),
+        subscription("b", &["US 1", "JP 1"]),
+      ]
+    };
+    let tags = |f: FilterCreateDto| -> Vec<String> {
+      filter_outbounds(&f.compile().unwrap(), &subscriptions())
+        .iter()
+        .map(|o| o["tag"].as_str().unwrap().to_string())
+        .collect()
+    };
+
+    let mut f = filter("simple", "US", None);
+    f.spec.limit = Some(2);
+    assert_eq!(tags(f), vec!["US 10", "US 2"]);
+
+    let mut f = filter("simple", "US", None);
+    f.spec.sort = FilterSort::Name;
+    assert_eq!(tags(f), vec!["US 1", "US 10", "US 2"]);
+
+    let mut f = filter("simple", "US", None);
+    f.spec.sort = FilterSort::Natural;
+    f.spec.limit = Some(2);
+    assert_eq!(tags(f), vec!["US 1", "US 2"]);
+
+    let mut f = filter("simple", "US", None);
+    f.spec.limit = Some(0);
+    assert!(f.compile().is_err());
+  }
+
+  #[test]
+  fn test_filter_scoped_to_subscriptions() {
+    let subscriptions = || {
+      vec![
+        subscription("a", &["HK-A1", "JP-A1"]),
+        subscription("b", &["HK-B1"]),
+      ]
+    };
+    let mut f = filter("simple", "HK", None);
+    f.spec.subscriptions = vec!["b".to_string()];
+    let f = f.compile().unwrap();
+
+    assert!(!f.draws_from("a"));
+    let all = subscriptions();
+    let scoped = filter_subscriptions(&f, &all);
+    assert_eq!(scoped.len(), 1);
+    assert_eq!(scoped[0].uuid, "b");
+
+    let tags: Vec<String> = filter_outbounds(&f, &subscriptions())
+      .iter()
+      .map(|o| o["tag"].as_str().unwrap().to_string())
+      .collect();
+    assert_eq!(tags, vec!["HK-B1"]);
+  }
+
+  #[test]
+  fn test_dedup_runs_once_before_every_filter() {
+    // Both subscriptions carry the same node and b has the higher priority, so every filter
+    // gets b's copy, including one scoped to a
+    let same_node = |tag: &str| json!({"type": "trojan", "tag": tag, "server": "example.com", "server_port": 443});
+    let subscriptions = vec![
+      SubscriptionOutbounds {
+        uuid: "a".to_string(),
+        name: "a".to_string(),
+        priority: 0,
+        outbounds: vec![same_node("HK-A1")],
+      },
+      SubscriptionOutbounds {
+        uuid: "b".to_string(),
+        name: "b".to_string(),
+        priority: 10,
+        outbounds: vec![same_node("HK-B1")],
+      },
+    ];
+    let dedup = DedupConfigDto {
+      enabled: true,
+      prefer_subscriptions: Vec::new(),
+    };
+    let deduped = dedup_subscriptions(subscriptions, Some(&dedup));
+    let tags = |f: FilterCreateDto| -> Vec<String> {
+      let f = f.compile().unwrap();
+      filter_outbounds(&f, &deduped)
+        .iter()
+        .map(|o| o["tag"].as_str().unwrap().to_string())
+        .collect()
+    };
+
+    assert_eq!(tags(filter("simple", "HK", None)), vec!["HK-B1"]);
+    assert_eq!(tags(filter("regex", r"-\w1$", None)), vec!["HK-B1"]);
+
+    let mut scoped = filter("simple", "HK", None);
+    scoped.spec.subscriptions = vec!["a".to_string()];
+    assert!(tags(scoped).is_empty());
+  }
+
+  #[tokio::test]
+  async fn test_filters_using_subscription() {
+    let dir = tempfile::tempdir().unwrap();
+    for (uuid, subscriptions) in [("hk", vec!["a"]), ("us", vec!["a", "b"]), ("all", vec![])] {
+      let mut scoped = filter("simple", "HK", None);
+      scoped.name = uuid.to_uppercase();
+      scoped.spec.subscriptions = subscriptions.into_iter().map(str::to_string).collect();
+      std::fs::write(
+        dir.path().join(format!("{}.json", uuid)),
+        serde_json::to_string(&scoped).unwrap(),
+      )
+      .unwrap();
+    }
+
+    let names = filters_using_subscription(dir.path(), "a").await.unwrap();
+    assert_eq!(names, vec!["HK", "US"]);
+    let names = filters_using_subscription(dir.path(), "c").await.unwrap();
+    assert!(names.is_empty());
+  }
+}
@@ -0,0 +1,423 @@
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use std::sync::{LazyLock, Mutex, MutexGuard};
+use tokio::fs;
+
+use crate::backend::api::config::DedupConfigDto;
+use crate::backend::api::config_generator::{
+  SubscriptionOutbounds, dedup_subscriptions, filter_outbounds, process_subscriptions,
+  read_subscriptions,
+};
+use crate::backend::api::config_validation::{IssueKind, ValidationIssue};
+use crate::backend::api::filter::{CompiledFilter, FilterCreateDto};
+use crate::backend::api::node_overrides::{NodeOverride, load_node_overrides};
+use crate::backend::api::outbound_group::OutboundGroupCreateDto;
+use crate::backend::api::rename_rules::load_global_rename_rules;
+use crate::backend::api::subscribe::SubscribeCreateDto;
+use crate::backend::error::AppError;
+use crate::backend::subscription_parser::{ParsedSubscription, parse_subscription};
+use crate::backend::tag_template::RenameRule;
+
+/// SHA-256 of the subscription content a parse result came from
+type ContentHash = [u8; 32];
+
+/// Parsed subscription content by subscription uuid, with the hash of the content it came from.
+/// One entry per subscription, replaced when its content changes.
+#[derive(Default)]
+pub(crate) struct ParseCache {
+  entries: HashMap<String, (ContentHash, ParsedSubscription)>,
+}
+
+impl ParseCache {
+  /// Parse a subscription's content, reusing the last result while the content is unchanged
+  pub(crate) fn parse(&mut self, uuid: &str, content: &str) -> ParsedSubscription {
+    self.parse_with(uuid, content, parse_subscription)
+  }
+
+  /// `parse` with the parser to run on a cache miss
+  pub(crate) fn parse_with(
+    &mut self,
+    uuid: &str,
+    content: &str,
+    parse: impl FnOnce(&str) -> ParsedSubscription,
+  ) -> ParsedSubscription {
+    let hash: ContentHash = Sha256::digest(content.as_bytes()).into();
+    if let Some((cached_hash, parsed)) = self.entries.get(uuid)
+      && *cached_hash == hash
+    {
+      return parsed.clone();
+    }
+
+    let parsed = parse(content);
+    self
+      .entries
+      .insert(uuid.to_string(), (hash, parsed.clone()));
+    parsed
+  }
+
+  /// Drop the parse result of a deleted subscription
+  pub(crate) fn forget(&mut self, uuid: &str) {
+    self.entries.remove(uuid);
+  }
+
+  /// Drop the parse results of every subscription not in `uuids`, e.g. files removed by hand
+  pub(crate) fn retain<'a>(&mut self, uuids: impl IntoIterator<Item = &'a str>) {
+    let uuids: HashSet<&str> = uuids.into_iter().collect();
+    self.entries.retain(|uuid, _| uuids.contains(uuid.as_str()));
+  }
+}
+
+/// Kept across requests so unchanged subscriptions are not decoded and parsed again
+static PARSE_CACHE: LazyLock<Mutex<ParseCache>> = LazyLock::new(Default::default);
+
+/// The shared parse cache; a poisoned lock only means a parse panicked, the entries are still valid
+pub(crate) fn parse_cache() -> MutexGuard<'static, ParseCache> {
+  PARSE_CACHE
+    .lock()
+    .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// Read every `<uuid>.json` file of a data directory as (uuid, content) pairs, skipping
+/// dotfiles such as .order.json; a missing directory has no files
+pub(crate) async fn read_json_dir(dir_path: &Path) -> Result<Vec<(String, String)>, AppError> {
+  let mut files = Vec::new();
+  if !dir_path.exists() {
+    return Ok(files);
+  }
+
+  let mut entries = fs::read_dir(dir_path).await?;
+  while let Some(entry) = entries.next_entry().await? {
+    let path = entry.path();
+    if path.extension().and_then(|s| s.to_str()) != Some("json") {
+      continue;
+    }
+    if let Some(uuid) = path.file_stem().and_then(|s| s.to_str())
+      && !uuid.starts_with('.')
+    {
+      files.push((uuid.to_string(), fs::read_to_string(&path).await?));
+    }
+  }
+  Ok(files)
+}
+
+/// Module directories under ./data that a generation reads
+const MODULE_DIRS: &[&str] = &[
+  "logs",
+  "dns-config",
+  "dns-server",
+  "inbounds",
+  "experimentals",
+  "outbounds",
+  "rulesets",
+  "rules",
+  "routes",
+  "outbound-group",
+  "filters",
+];
+
+/// What a UUID in an outbound position (route rule, final, group member, detour) refers to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum OutboundKind {
+  Group,
+  Filter,
+  Outbound,
+}
+
+/// Snapshot of ./data taken at the start of one config generation. Every module, subscription,
+/// the global rename rules and the node overrides are read once, so a generation sees consistent
+/// data even if files change while it runs; subscriptions are processed once and every filter is
+/// compiled once, however many groups use them.
+#[derive(Default)]
+pub(crate) struct GenerationContext {
+  /// Raw module files by directory, then uuid
+  modules: HashMap<&'static str, HashMap<String, String>>,
+  /// Subscription files in the saved order
+  subscribes: Vec<SubscribeCreateDto>,
+  rename_rules: Vec<RenameRule>,
+  overrides: Vec<NodeOverride>,
+  /// Nodes of `subscribes`, processed on first use
+  subscriptions: Option<Vec<SubscriptionOutbounds>>,
+  /// Cross-subscription dedup of the config being generated
+  dedup: Option<DedupConfigDto>,
+  filters: HashMap<String, CompiledFilter>,
+  /// Problems generation worked around, for the validation report
+  warnings: Vec<ValidationIssue>,
+  /// Problem generation stopped at, for the validation report
+  errors: Vec<ValidationIssue>,
+}
+
+impl GenerationContext {
+  /// Index every module file under ./data and read the subscriptions with their naming and
+  /// override rules
+  pub(crate) async fn load() -> Result<Self, AppError> {
+    let mut context = Self::default();
+    for module_type in MODULE_DIRS {
+      for (uuid, content) in read_json_dir(&Path::new("./data").join(module_type)).await? {
+        context.insert_module(module_type, &uuid, content);
+      }
+    }
+
+    for subscribe in read_subscriptions(Path::new("./data/subscribes")).await? {
+      context.insert_subscription(subscribe);
+    }
+    parse_cache().retain(context.subscribes.iter().map(|s| s.uuid.as_str()));
+    context.rename_rules = load_global_rename_rules().await?;
+    context.overrides = load_node_overrides().await?;
+    Ok(context)
+  }
+
+  /// Add a module file to the index
+  pub(crate) fn insert_module(&mut self, module_type: &'static str, uuid: &str, content: String) {
+    self
+      .modules
+      .entry(module_type)
+      .or_default()
+      .insert(uuid.to_string(), content);
+  }
+
+  /// Add a subscription after the ones already loaded
+  pub(crate) fn insert_subscription(&mut self, subscribe: SubscribeCreateDto) {
+    self.subscriptions = None;
+    self.subscribes.push(subscribe);
+  }
+
+  pub(crate) fn has_module(&self, module_type: &str, uuid: &str) -> bool {
+    self
+      .modules
+      .get(module_type)
+      .is_some_and(|modules| modules.contains_key(uuid))
+  }
+
+  fn raw_module(&self, module_type: &str, uuid: &str) -> Result<&str, AppError> {
+    self
+      .modules
+      .get(module_type)
+      .and_then(|modules| modules.get(uuid))
+      .map(String::as_str)
+      .ok_or_else(|| AppError::NotFound(format!("{} module not found: {}", module_type, uuid)))
+  }
+
+  /// Parse a module's "json" field
+  pub(crate) fn module_json(&self, module_type: &str, uuid: &str) -> Result<Value, AppError> {
+    let module: Value = serde_json::from_str(self.raw_module(module_type, uuid)?)?;
+
+    // Extract and parse the "json" field
+    if let Some(json_str) = module.get("json").and_then(|j| j.as_str()) {
+      serde_json::from_str(json_str).map_err(|e| {
+        AppError::InternalServerError(format!(
+          "Failed to parse {} module JSON: {}",
+          module_type, e
+        ))
+      })
+    } else {
+      Err(AppError::InternalServerError(format!(
+        "{} module missing 'json' field",
+        module_type
+      )))
+    }
+  }
+
+  /// Parse a module's "json" field, injecting the module's "name" as "tag" if the JSON has none.
+  /// This is used for outbound modules where the JSON may not contain a "tag" field.
+  pub(crate) fn module_json_with_tag(
+    &self,
+    module_type: &str,
+    uuid: &str,
+  ) -> Result<Value, AppError> {
+    let mut parsed = self.module_json(module_type, uuid)?;
+    let module: Value = serde_json::from_str(self.raw_module(module_type, uuid)?)?;
+    let module_name = module
+      .get("name")
+      .and_then(|n| n.as_str())
+      .unwrap_or_default();
+
+    if let Some(obj) = parsed.as_object_mut()
+      && !obj.contains_key("tag")
+      && !module_name.is_empty()
+    {
+      obj.insert("tag".to_string(), Value::String(module_name.to_string()));
+    }
+    Ok(parsed)
+  }
+
+  /// Resolve a UUID in an outbound position; fails if it is unknown or found in several places
+  pub(crate) fn outbound_kind(&self, uuid: &str) -> Result<OutboundKind, AppError> {
+    let kinds: Vec<OutboundKind> = [
+      ("outbound-group", OutboundKind::Group),
+      ("filters", OutboundKind::Filter),
+      ("outbounds", OutboundKind::Outbound),
+    ]
+    .into_iter()
+    .filter(|(module_type, _)| self.has_module(module_type, uuid))
+    .map(|(_, kind)| kind)
+    .collect();
+
+    match kinds.as_slice() {
+      [kind] => Ok(*kind),
+      [] => Err(AppError::NotFound(format!("Outbound not found: {}", uuid))),
+      _ => Err(AppError::InternalServerError(format!(
+        "UUID {} is ambiguous: it names more than one of {:?}",
+        uuid, kinds
+      ))),
+    }
+  }
+
+  pub(crate) fn group(&self, uuid: &str) -> Result<OutboundGroupCreateDto, AppError> {
+    let content = self
+      .raw_module("outbound-group", uuid)
+      .map_err(|_| AppError::NotFound(format!("Outbound group not found: {}", uuid)))?;
+    Ok(serde_json::from_str(content)?)
+  }
+
+  /// Tag an outbound-position UUID resolves to: a group's name or an outbound's tag
+  pub(crate) fn outbound_tag(&self, uuid: &str) -> Result<String, AppError> {
+    match self.outbound_kind(uuid)? {
+      OutboundKind::Group => Ok(self.group(uuid)?.name),
+      OutboundKind::Filter => Err(AppError::BadRequest(format!(
+        "Filter {} cannot be used directly as an outbound, put it in an outbound group",
+        uuid
+      ))),
+      OutboundKind::Outbound => Ok(
+        self
+          .module_json_with_tag("outbounds", uuid)?
+          .get("tag")
+          .and_then(|t| t.as_str())
+          .unwrap_or_default()
+          .to_string(),
+      ),
+    }
+  }
+
+  /// Compile a filter on first use
+  pub(crate) fn filter(&mut self, uuid: &str) -> Result<&CompiledFilter, AppError> {
+    if !self.filters.contains_key(uuid) {
+      let content = self
+        .raw_module("filters", uuid)
+        .map_err(|_| AppError::NotFound(format!("Filter not found: {}", uuid)))?;
+      let compiled = serde_json::from_str::<FilterCreateDto>(content)?.compile()?;
+      self.filters.insert(uuid.to_string(), compiled);
+    }
+    Ok(&self.filters[uuid])
+  }
+
+  /// Log a problem generation works around and keep it for the validation report
+  pub(crate) fn warn(&mut self, kind: IssueKind, message: String) {
+    log::warn!("{}", message);
+    self.warnings.push(ValidationIssue { kind, message });
+  }
+
+  pub(crate) fn take_warnings(&mut self) -> Vec<ValidationIssue> {
+    std::mem::take(&mut self.warnings)
+  }
+
+  /// Error that stops generation, kept for the validation report with its kind
+  pub(crate) fn reject(&mut self, kind: IssueKind, message: String) -> AppError {
+    self.errors.push(ValidationIssue {
+      kind,
+      message: message.clone(),
+    });
+    AppError::BadRequest(message)
+  }
+
+  pub(crate) fn take_errors(&mut self) -> Vec<ValidationIssue> {
+    std::mem::take(&mut self.errors)
+  }
+
+  /// Deduplicate subscription nodes with the config's settings before any filter runs
+  pub(crate) fn set_dedup(&mut self, dedup: Option<DedupConfigDto>) {
+    self.dedup = dedup;
+  }
+
+  /// Processed nodes of every enabled subscription, loaded and deduplicated on first use
+  pub(crate) async fn subscriptions(&mut self) -> Result<&[SubscriptionOutbounds], AppError> {
+    if self.subscriptions.is_none() {
+      let subscriptions = process_subscriptions(
+        &self.subscribes,
+        &self.rename_rules,
+        &self.overrides,
+        &mut parse_cache(),
+      )?;
+      self.subscriptions = Some(dedup_subscriptions(subscriptions, self.dedup.as_ref()));
+    }
+    Ok(self.subscriptions.as_deref().unwrap_or_default())
+  }
+
+  /// Nodes a filter selects, from the subscriptions loaded for this generation
+  pub(crate) async fn apply_filter(&mut self, uuid: &str) -> Result<Vec<Value>, AppError> {
+    self.filter(uuid)?;
+    self.subscriptions().await?;
+
+    let filter = &self.filters[uuid];
+    Ok(filter_outbounds(
+      filter,
+      self.subscriptions.as_deref().unwrap_or_default(),
+    ))
+  }
+}
+
+/// A module file as the module APIs store it, with `json` as the module's config
+#[cfg(test)]
+pub(crate) fn test_module(name: &str, json: Value) -> String {
+  serde_json::json!({"uuid": "", "name": name, "json": json.to_string()}).to_string()
+}
+
+#[cfg(test)]
+impl GenerationContext {
+  /// Context shared by the generation and validation tests: one module of each kind, an empty
+  /// group nested in another, and a rule using a rule_set that is never defined
+  pub(crate) fn fixture() -> Self {
+    use serde_json::json;
+
+    let mut context = Self::default();
+    context.insert_module("logs", "log", test_module("Log", json!({"level": "info"})));
+    context.insert_module("experimentals", "exp", test_module("Exp", json!({})));
+    context.insert_module(
+      "dns-server",
+      "dns",
+      test_module("Local", json!({"type": "local", "tag": "local"})),
+    );
+    context.insert_module(
+      "inbounds",
+      "mixed",
+      test_module("Mixed", json!({"type": "mixed", "tag": "mixed-in"})),
+    );
+    context.insert_module(
+      "outbounds",
+      "direct",
+      test_module("Direct", json!({"type": "direct"})),
+    );
+    context.insert_module(
+      "outbounds",
+      "proxy",
+      test_module("Proxy", json!({"type": "trojan", "tag": "proxy-tag"})),
+    );
+    for (uuid, name, group_type, members) in [
+      ("group", "Auto", "urltest", json!(["proxy"])),
+      ("empty", "Empty", "selector", json!(["nested"])),
+      ("nested", "Nested", "selector", json!([])),
+    ] {
+      context.insert_module(
+        "outbound-group",
+        uuid,
+        json!({"uuid": uuid, "name": name, "group_type": group_type, "outbounds": members})
+          .to_string(),
+      );
+    }
+    context.insert_module(
+      "filters",
+      "filter",
+      json!({"uuid": "filter", "name": "HK", "filter_type": "simple", "pattern": "HK"}).to_string(),
+    );
+    context.insert_module(
+      "rules",
+      "rule",
+      test_module(
+        "Rule",
+        json!({"rule_set": "geoip-cn", "outbound": "Direct"}),
+      ),
+    );
+    context
+  }
+}
@@ -0,0 +1,92 @@
+#[cfg(test)]
+mod tests {
+  use super::super::generation_context::{GenerationContext, OutboundKind, test_module};
+  use super::super::subscribe::SubscribeCreateDto;
+  use crate::backend::error::AppError;
+  use serde_json::json;
+
+  fn context() -> GenerationContext {
+    GenerationContext::fixture()
+  }
+
+  #[test]
+  fn test_outbound_kind() {
+    let context = context();
+    assert_eq!(context.outbound_kind("group").unwrap(), OutboundKind::Group);
+    assert_eq!(
+      context.outbound_kind("filter").unwrap(),
+      OutboundKind::Filter
+    );
+    assert_eq!(
+      context.outbound_kind("proxy").unwrap(),
+      OutboundKind::Outbound
+    );
+    assert!(matches!(
+      context.outbound_kind("missing"),
+      Err(AppError::NotFound(_))
+    ));
+  }
+
+  #[test]
+  fn test_outbound_kind_rejects_ambiguous_uuid() {
+    let mut context = context();
+    context.insert_module(
+      "outbounds",
+      "group",
+      test_module("Shadow", json!({"type": "direct"})),
+    );
+    assert!(context.outbound_kind("group").is_err());
+    assert!(context.outbound_tag("group").is_err());
+  }
+
+  #[test]
+  fn test_outbound_tag() {
+    let mut context = context();
+    assert_eq!(context.outbound_tag("group").unwrap(), "Auto");
+    // Outbounds without a tag take the module name
+    assert_eq!(context.outbound_tag("direct").unwrap(), "Direct");
+    assert_eq!(context.outbound_tag("proxy").unwrap(), "proxy-tag");
+    assert!(context.outbound_tag("filter").is_err());
+    assert_eq!(context.filter("filter").unwrap().name, "HK");
+  }
+
+  #[test]
+  fn test_module_json_errors() {
+    let mut context = context();
+    context.insert_module("logs", "broken", json!({"name": "Broken"}).to_string());
+    assert!(matches!(
+      context.module_json("logs", "missing"),
+      Err(AppError::NotFound(_))
+    ));
+    assert!(matches!(
+      context.module_json("logs", "broken"),
+      Err(AppError::InternalServerError(_))
+    ));
+  }
+
+  #[tokio::test]
+  async fn test_subscriptions_come_from_the_snapshot() {
+    let mut context = context();
+    for (uuid, enabled) in [("snapshot-a", true), ("snapshot-off", false)] {
+      context.insert_subscription(SubscribeCreateDto {
+        uuid: uuid.to_string(),
+        name: uuid.to_string(),
+        json: json!({
+          "source": "inline",
+          "enabled": enabled,
+          "content": "trojan://pw@hk.example.com:443?security=tls#HK 1"
+        })
+        .to_string(),
+      });
+    }
+
+    let subscriptions = context.subscriptions().await.unwrap();
+    assert_eq!(subscriptions.len(), 1);
+    assert_eq!(subscriptions[0].uuid, "snapshot-a");
+    assert_eq!(subscriptions[0].outbounds[0]["tag"], "HK 1-snapshot-a");
+    assert_eq!(
+      context.apply_filter("filter").await.unwrap()[0]["tag"],
+      "HK 1-snapshot-a"
+    );
+  }
+}
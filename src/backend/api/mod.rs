@@ -1,21 +1,38 @@
 pub mod backup;
 pub mod config;
 pub mod config_generator;
+pub mod config_validation;
 pub mod dns;
 pub mod dns_config;
 pub mod experimental;
 pub mod filter;
+pub mod generation_context;
 pub mod inbound;
 pub mod log;
+pub mod node_overrides;
 pub mod outbound;
 pub mod outbound_group;
+pub mod rename_rules;
 pub mod route;
 pub mod rule;
 pub mod ruleset;
 pub mod subscribe;
+pub mod subscribe_history;
 pub mod usage_check;
 
 #[cfg(test)]
 mod config_generator_test;
 #[cfg(test)]
+mod config_validation_test;
+#[cfg(test)]
+mod filter_test;
+#[cfg(test)]
+mod generation_context_test;
+#[cfg(test)]
+mod node_overrides_test;
+#[cfg(test)]
 mod outbound_group_test;
+#[cfg(test)]
+mod subscribe_history_test;
+#[cfg(test)]
+mod subscribe_test;
@@ -0,0 +1,195 @@
+use axum::{Json, http::StatusCode, response::IntoResponse};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::path::Path;
+use tokio::fs;
+
+use crate::backend::api::filter::{CompiledFilter, FilterCreateDto};
+use crate::backend::error::AppError;
+
+/// Override rules applied to subscription nodes, in order
+const NODE_OVERRIDES_PATH: &str = "./data/subscribes/.overrides.json";
+
+/// Fields a patch may not touch: groups and filters refer to nodes by tag
+const PROTECTED_FIELDS: &[&str] = &["tag", "type"];
+
+/// Patch applied to the subscription nodes a rule matches
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NodeOverrideRule {
+  #[serde(default)]
+  pub name: String,
+  /// Subscription uuids the rule applies to; empty matches every subscription
+  #[serde(default)]
+  pub subscriptions: Vec<String>,
+  /// Filter uuid selecting nodes; all nodes of the matched subscriptions when unset
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub filter: Option<String>,
+  /// JSON merge patch (RFC 7386) applied to each matched outbound
+  pub patch: Value,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct NodeOverridesDto {
+  pub rules: Vec<NodeOverrideRule>,
+}
+
+/// Override rule with its filter loaded
+#[derive(Debug)]
+pub struct NodeOverride {
+  pub subscriptions: Vec<String>,
+  pub filter: Option<CompiledFilter>,
+  pub patch: Value,
+}
+
+impl NodeOverride {
+  fn matches(&self, subscription_uuid: &str, outbound: &Value) -> bool {
+    if !self.subscriptions.is_empty() && !self.subscriptions.iter().any(|s| s == subscription_uuid)
+    {
+      return false;
+    }
+    self
+      .filter
+      .as_ref()
+      .is_none_or(|filter| filter.matches(outbound, subscription_uuid))
+  }
+}
+
+/// Apply a JSON merge patch (RFC 7386): objects merge recursively, `null` removes a key and
+/// any other value replaces the target
+pub fn merge_patch(target: &mut Value, patch: &Value) {
+  let Value::Object(patch_obj) = patch else {
+    *target = patch.clone();
+    return;
+  };
+
+  if !target.is_object() {
+    *target = Value::Object(serde_json::Map::new());
+  }
+  if let Value::Object(target_obj) = target {
+    for (key, value) in patch_obj {
+      if value.is_null() {
+        target_obj.remove(key);
+      } else {
+        merge_patch(target_obj.entry(key.clone()).or_insert(Value::Null), value);
+      }
+    }
+  }
+}
+
+/// Patch the outbounds of one subscription with every matching rule, in rule order
+pub fn apply_node_overrides(
+  outbounds: &mut [Value],
+  subscription_uuid: &str,
+  overrides: &[NodeOverride],
+) {
+  for outbound in outbounds.iter_mut() {
+    for rule in overrides {
+      if rule.matches(subscription_uuid, outbound) {
+        merge_patch(outbound, &rule.patch);
+      }
+    }
+  }
+}
+
+/// Check that a rule's patch is an object leaving the node identity alone
+pub fn validate_override_rule(rule: &NodeOverrideRule) -> Result<(), AppError> {
+  let Some(patch) = rule.patch.as_object() else {
+    return Err(AppError::BadRequest(format!(
+      "Override \"{}\": patch must be a JSON object",
+      rule.name
+    )));
+  };
+  if let Some(field) = PROTECTED_FIELDS.iter().find(|f| patch.contains_key(**f)) {
+    return Err(AppError::BadRequest(format!(
+      "Override \"{}\": patch must not change \"{}\"",
+      rule.name, field
+    )));
+  }
+  Ok(())
+}
+
+async fn load_override_rules() -> Result<Vec<NodeOverrideRule>, AppError> {
+  let path = Path::new(NODE_OVERRIDES_PATH);
+  if !path.exists() {
+    return Ok(Vec::new());
+  }
+
+  let content = fs::read_to_string(path).await?;
+  let overrides: NodeOverridesDto = serde_json::from_str(&content)?;
+  Ok(overrides.rules)
+}
+
+async fn load_filter(uuid: &str) -> Result<Option<FilterCreateDto>, AppError> {
+  let file_path = Path::new("./data/filters").join(format!("{}.json", uuid));
+  if !file_path.exists() {
+    return Ok(None);
+  }
+
+  let content = fs::read_to_string(&file_path).await?;
+  Ok(Some(serde_json::from_str(&content)?))
+}
+
+/// Load the override rules with their filters; rules pointing at a deleted filter are skipped
+pub async fn load_node_overrides() -> Result<Vec<NodeOverride>, AppError> {
+  let mut overrides = Vec::new();
+  for rule in load_override_rules().await? {
+    let filter = match &rule.filter {
+      Some(uuid) => match load_filter(uuid).await? {
+        Some(filter) => match filter.compile() {
+          Ok(compiled) => Some(compiled),
+          Err(e) => {
+            log::warn!("Skipping override \"{}\": {:?}", rule.name, e);
+            continue;
+          }
+        },
+        None => {
+          log::warn!(
+            "Skipping override \"{}\": filter {} not found",
+            rule.name,
+            uuid
+          );
+          continue;
+        }
+      },
+      None => None,
+    };
+    overrides.push(NodeOverride {
+      subscriptions: rule.subscriptions,
+      filter,
+      patch: rule.patch,
+    });
+  }
+  Ok(overrides)
+}
+
+pub async fn get_node_overrides() -> Result<impl IntoResponse, AppError> {
+  Ok(Json(NodeOverridesDto {
+    rules: load_override_rules().await?,
+  }))
+}
+
+pub async fn update_node_overrides(
+  Json(payload): Json<NodeOverridesDto>,
+) -> Result<impl IntoResponse, AppError> {
+  for rule in &payload.rules {
+    validate_override_rule(rule)?;
+    if let Some(uuid) = &rule.filter
+      && load_filter(uuid).await?.is_none()
+    {
+      return Err(AppError::BadRequest(format!(
+        "Override \"{}\": filter not found: {}",
+        rule.name, uuid
+      )));
+    }
+  }
+
+  let path = Path::new(NODE_OVERRIDES_PATH);
+  if let Some(dir) = path.parent()
+    && !dir.exists()
+  {
+    fs::create_dir_all(dir).await?;
+  }
+  fs::write(path, serde_json::to_string_pretty(&payload)?.as_bytes()).await?;
+
+  Ok((StatusCode::OK, "Node overrides updated successfully").into_response())
+}
@@ -0,0 +1,119 @@
+#[cfg(test)]
+mod tests {
+  use super::super::filter::{FilterCreateDto, FilterSpec};
+  use super::super::node_overrides::{
+    NodeOverride, NodeOverrideRule, apply_node_overrides, merge_patch, validate_override_rule,
+  };
+  use serde_json::json;
+
+  fn node(tag: &str) -> serde_json::Value {
+    json!({
+      "type": "vless",
+      "tag": tag,
+      "server": "a.example.com",
+      "server_port": 443,
+      "tls": {"enabled": true, "utls": {"enabled": true, "fingerprint": "firefox"}}
+    })
+  }
+
+  #[test]
+  fn test_merge_patch() {
+    let mut target = node("HK");
+    merge_patch(
+      &mut target,
+      &json!({
+        "tls": {"utls": {"fingerprint": "chrome"}},
+        "tcp_fast_open": true,
+        "server_port": null
+      }),
+    );
+
+    assert_eq!(target["tls"]["utls"]["fingerprint"], "chrome");
+    assert_eq!(target["tls"]["utls"]["enabled"], true);
+    assert_eq!(target["tls"]["enabled"], true);
+    assert_eq!(target["tcp_fast_open"], true);
+    assert!(target.get("server_port").is_none());
+  }
+
+  #[test]
+  fn test_merge_patch_replaces_non_objects() {
+    let mut target = json!({"multiplex": true});
+    merge_patch(
+      &mut target,
+      &json!({"multiplex": {"enabled": true, "protocol": "h2mux"}}),
+    );
+    assert_eq!(target["multiplex"]["protocol"], "h2mux");
+  }
+
+  #[test]
+  fn test_apply_overrides_by_subscription_and_filter() {
+    let hk_filter = FilterCreateDto {
+      uuid: "f1".to_string(),
+      name: "HK".to_string(),
+      spec: FilterSpec {
+        pattern: "HK".to_string(),
+        ..Default::default()
+      },
+    }
+    .compile()
+    .unwrap();
+    let overrides = vec![
+      NodeOverride {
+        subscriptions: vec!["sub-a".to_string()],
+        filter: None,
+        patch: json!({"domain_strategy": "prefer_ipv4"}),
+      },
+      NodeOverride {
+        subscriptions: Vec::new(),
+        filter: Some(hk_filter),
+        patch: json!({"detour": "relay"}),
+      },
+    ];
+
+    let mut a = vec![node("HK-01"), node("JP-01")];
+    apply_node_overrides(&mut a, "sub-a", &overrides);
+    assert_eq!(a[0]["domain_strategy"], "prefer_ipv4");
+    assert_eq!(a[0]["detour"], "relay");
+    assert_eq!(a[1]["domain_strategy"], "prefer_ipv4");
+    assert!(a[1].get("detour").is_none());
+
+    let mut b = vec![node("HK-02")];
+    apply_node_overrides(&mut b, "sub-b", &overrides);
+    assert!(b[0].get("domain_strategy").is_none());
+    assert_eq!(b[0]["detour"], "relay");
+  }
+
+  #[test]
+  fn test_later_rules_win() {
+    let overrides = vec![
+      NodeOverride {
+        subscriptions: Vec::new(),
+        filter: None,
+        patch: json!({"tls": {"utls": {"fingerprint": "chrome"}}}),
+      },
+      NodeOverride {
+        subscriptions: Vec::new(),
+        filter: None,
+        patch: json!({"tls": {"utls": {"fingerprint": "safari"}}}),
+      },
+    ];
+    let mut outbounds = vec![node("HK")];
+    apply_node_overrides(&mut outbounds, "sub", &overrides);
+    assert_eq!(outbounds[0]["tls"]["utls"]["fingerprint"], "safari");
+  }
+
+  #[test]
+  fn test_validate_override_rule() {
+    let rule = |patch: serde_json::Value| NodeOverrideRule {
+      name: "r".to_string(),
+      subscriptions: Vec::new(),
+      filter: None,
+      patch,
+    };
+
+    assert!(validate_override_rule(&rule(json!({"tcp_fast_open": true}))).is_ok());
+    assert!(validate_override_rule(&rule(json!("chrome"))).is_err());
+    assert!(validate_override_rule(&rule(json!({"tag": "renamed"}))).is_err());
+    assert!(validate_override_rule(&rule(json!({"type": "direct"}))).is_err());
+  }
+}
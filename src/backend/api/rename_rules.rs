@@ -0,0 +1,118 @@
+use axum::{Json, http::StatusCode, response::IntoResponse};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::path::Path;
+use tokio::fs;
+
+use crate::backend::api::subscribe::SubscribeCreateDto;
+use crate::backend::error::AppError;
+use crate::backend::pseudo_nodes::{PseudoNodeFilter, exclude_pseudo_nodes};
+use crate::backend::subscription_parser::parse_subscription_content;
+use crate::backend::tag_template::{
+  RenameRule, apply_rename_rules, assign_tags, compile_rename_rules,
+};
+
+/// Rename rules applied to every subscription, before its own rules
+const GLOBAL_RENAME_RULES_PATH: &str = "./data/subscribes/.rename_rules.json";
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct RenameRulesDto {
+  pub rules: Vec<RenameRule>,
+}
+
+/// Read the global rename rules (empty if none were saved)
+pub async fn load_global_rename_rules() -> Result<Vec<RenameRule>, AppError> {
+  let path = Path::new(GLOBAL_RENAME_RULES_PATH);
+  if !path.exists() {
+    return Ok(Vec::new());
+  }
+
+  let content = fs::read_to_string(path).await?;
+  let rules: RenameRulesDto = serde_json::from_str(&content)?;
+  Ok(rules.rules)
+}
+
+pub async fn get_rename_rules() -> Result<impl IntoResponse, AppError> {
+  Ok(Json(RenameRulesDto {
+    rules: load_global_rename_rules().await?,
+  }))
+}
+
+pub async fn update_rename_rules(
+  Json(payload): Json<RenameRulesDto>,
+) -> Result<impl IntoResponse, AppError> {
+  compile_rename_rules(&payload.rules)?;
+
+  let path = Path::new(GLOBAL_RENAME_RULES_PATH);
+  if let Some(dir) = path.parent()
+    && !dir.exists()
+  {
+    fs::create_dir_all(dir).await?;
+  }
+  fs::write(path, serde_json::to_string_pretty(&payload)?.as_bytes()).await?;
+
+  Ok((StatusCode::OK, "Rename rules updated successfully").into_response())
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RenamePreviewDto {
+  pub uuid: String,
+  /// Draft rules for the subscription; the saved `rename_rules` are used when omitted
+  pub rules: Option<Vec<RenameRule>>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct RenamePreviewItemDto {
+  /// Node name from the provider
+  pub before: String,
+  /// Name after the rename rules
+  pub renamed: String,
+  /// Final tag after the tag template; None if the node is skipped as a duplicate
+  pub after: Option<String>,
+}
+
+/// Show how a subscription's node names change under the global and subscription rules
+pub async fn preview_rename(
+  Json(payload): Json<RenamePreviewDto>,
+) -> Result<impl IntoResponse, AppError> {
+  let file_path = Path::new("./data/subscribes").join(format!("{}.json", payload.uuid));
+  if !file_path.exists() {
+    return Err(AppError::NotFound("Subscribe not found".to_string()));
+  }
+
+  let content = fs::read_to_string(&file_path).await?;
+  let subscribe: SubscribeCreateDto = serde_json::from_str(&content)?;
+  let metadata: serde_json::Value = serde_json::from_str(&subscribe.json)
+    .map_err(|e| AppError::from(anyhow::anyhow!("Invalid subscription metadata: {}", e)))?;
+  let mut typed = subscribe.metadata()?;
+  if let Some(rules) = &payload.rules {
+    typed.rename_rules = rules.clone();
+  }
+
+  let global_rules = load_global_rename_rules().await?;
+  let naming = typed.tag_naming(&global_rules);
+  let compiled = compile_rename_rules(&naming.rename_rules)?;
+
+  let outbounds = parse_subscription_content(&typed.content);
+  let (outbounds, _) = exclude_pseudo_nodes(outbounds, &PseudoNodeFilter::from_metadata(&metadata));
+  let tags = assign_tags(&outbounds, &subscribe.name, &naming, &mut HashSet::new())?;
+
+  let preview: Vec<RenamePreviewItemDto> = outbounds
+    .iter()
+    .zip(tags)
+    .map(|(outbound, after)| {
+      let before = outbound
+        .get("tag")
+        .and_then(|t| t.as_str())
+        .unwrap_or_default()
+        .to_string();
+      RenamePreviewItemDto {
+        renamed: apply_rename_rules(&compiled, &before),
+        before,
+        after,
+      }
+    })
+    .collect();
+
+  Ok(Json(preview))
+}
@@ -1,10 +1,22 @@
-use axum::{Json, http::StatusCode, response::IntoResponse};
+use axum::{Json, extract::Multipart, http::StatusCode, response::IntoResponse};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::path::Path;
 use tokio::fs;
 
+use crate::backend::api::filter::filters_using_subscription;
+use crate::backend::api::generation_context::parse_cache;
+use crate::backend::api::node_overrides::{apply_node_overrides, load_node_overrides};
+use crate::backend::api::rename_rules::load_global_rename_rules;
+use crate::backend::api::subscribe_history;
 use crate::backend::error::AppError;
+use crate::backend::pseudo_nodes::{
+  ExcludedNode, PseudoNodeFilter, default_enabled, exclude_pseudo_nodes,
+};
+use crate::backend::tag_template::{
+  DEFAULT_TAG_TEMPLATE, RenameRule, TagCollisionStrategy, TagNaming, apply_tag_naming,
+  check_tag_template, compile_rename_rules,
+};
 
 #[derive(Debug, Deserialize, Serialize)]
 pub struct SubscribeCreateDto {
@@ -35,6 +47,8 @@ pub async fn create_subscribe(
     );
   }
 
+  payload.metadata()?.validate()?;
+
   fs::write(file_path, serde_json::to_string(&payload)?.as_bytes()).await?;
 
   Ok((StatusCode::CREATED, "Subscribe created successfully").into_response())
@@ -45,6 +59,8 @@ pub struct SubscribeListDto {
   pub uuid: String,
   pub name: String,
   pub json: String,
+  /// Traffic and expiry reported by the provider on the last refresh
+  pub userinfo: Option<SubscriptionUserinfo>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -78,28 +94,22 @@ async fn write_subscribe_order(uuids: Vec<String>) -> Result<(), AppError> {
   Ok(())
 }
 
-pub async fn list_subscribes() -> Result<impl IntoResponse, AppError> {
+/// Load all subscriptions, sorted by the saved order (new ones not in order yet come last)
+async fn load_subscribes_in_order() -> Result<Vec<SubscribeCreateDto>, AppError> {
   let dir_path = Path::new("./data/subscribes");
   if !dir_path.exists() {
-    return Ok(Json(Vec::<SubscribeListDto>::new()));
+    return Ok(Vec::new());
   }
 
   let mut entries = fs::read_dir(dir_path).await?;
-  let mut subscribes_map: HashMap<String, SubscribeListDto> = HashMap::new();
+  let mut subscribes_map: HashMap<String, SubscribeCreateDto> = HashMap::new();
 
   while let Some(entry) = entries.next_entry().await? {
     let path = entry.path();
     if path.extension().and_then(|s| s.to_str()) == Some("json") {
       let content = fs::read_to_string(&path).await?;
       if let Ok(subscribe_dto) = serde_json::from_str::<SubscribeCreateDto>(&content) {
-        subscribes_map.insert(
-          subscribe_dto.uuid.clone(),
-          SubscribeListDto {
-            uuid: subscribe_dto.uuid,
-            name: subscribe_dto.name,
-            json: subscribe_dto.json,
-          },
-        );
+        subscribes_map.insert(subscribe_dto.uuid.clone(), subscribe_dto);
       }
     }
   }
@@ -120,6 +130,27 @@ pub async fn list_subscribes() -> Result<impl IntoResponse, AppError> {
     subscribes.push(subscribe);
   }
 
+  Ok(subscribes)
+}
+
+/// Read the stored userinfo from a subscription's metadata JSON
+fn read_userinfo(json: &str) -> Option<SubscriptionUserinfo> {
+  let metadata: serde_json::Value = serde_json::from_str(json).ok()?;
+  serde_json::from_value(metadata.get("userinfo")?.clone()).ok()
+}
+
+pub async fn list_subscribes() -> Result<impl IntoResponse, AppError> {
+  let subscribes = load_subscribes_in_order()
+    .await?
+    .into_iter()
+    .map(|subscribe| SubscribeListDto {
+      userinfo: read_userinfo(&subscribe.json),
+      uuid: subscribe.uuid,
+      name: subscribe.name,
+      json: subscribe.json,
+    })
+    .collect::<Vec<_>>();
+
   Ok(Json(subscribes))
 }
 
@@ -141,17 +172,56 @@ pub async fn update_subscribe(
     return Ok((StatusCode::NOT_FOUND, "Subscribe not found").into_response());
   }
 
+  // The editor sends back the metadata it loaded; keep what refreshes wrote since then
+  let stored: SubscribeCreateDto = serde_json::from_str(&fs::read_to_string(&file_path).await?)?;
+  let parse = |json: &str| {
+    serde_json::from_str::<serde_json::Map<String, serde_json::Value>>(json)
+      .map_err(|e| AppError::BadRequest(format!("Invalid subscription metadata: {}", e)))
+  };
+  let metadata = merge_editable_metadata(&parse(&stored.json)?, parse(&payload.json)?);
+
   let storage_dto = SubscribeCreateDto {
     uuid: payload.uuid,
     name: payload.name,
-    json: payload.json,
+    json: serde_json::to_string(&metadata)?,
   };
+  storage_dto.metadata()?.validate()?;
 
   fs::write(file_path, serde_json::to_string(&storage_dto)?.as_bytes()).await?;
 
   Ok((StatusCode::OK, "Subscribe updated successfully").into_response())
 }
 
+/// Metadata fields written by refresh, upload and paste rather than by the editor
+const SERVER_OWNED_FIELDS: &[&str] = &[
+  "content",
+  "source",
+  "file_name",
+  "last_updated",
+  "last_failure_at",
+  "last_error",
+  "parse_summary",
+  "userinfo",
+];
+
+/// Take the user-editable fields from `edited` and the server-owned ones from `stored`
+pub(crate) fn merge_editable_metadata(
+  stored: &serde_json::Map<String, serde_json::Value>,
+  mut edited: serde_json::Map<String, serde_json::Value>,
+) -> serde_json::Map<String, serde_json::Value> {
+  for field in SERVER_OWNED_FIELDS {
+    match stored.get(*field) {
+      Some(value) => {
+        edited.insert(field.to_string(), value.clone());
+      }
+      None => {
+        edited.remove(*field);
+      }
+    }
+  }
+  edited
+}
+
 #[derive(Debug, Deserialize)]
 pub struct SubscribeDeleteDto {
   pub uuid: String,
@@ -168,7 +238,21 @@ pub async fn delete_subscribe(
     return Ok((StatusCode::NOT_FOUND, "Subscribe not found").into_response());
   }
 
+  // Dropping the uuid from a filter would widen it to every subscription, so refuse instead
+  let filters = filters_using_subscription(Path::new("./data/filters"), &payload.uuid).await?;
+  if !filters.is_empty() {
+    return Ok(
+      (
+        StatusCode::CONFLICT,
+        format!("Subscribe is used by filters: {}", filters.join(", ")),
+      )
+        .into_response(),
+    );
+  }
+
   fs::remove_file(file_path).await?;
+  subscribe_history::delete_history(&payload.uuid).await?;
+  parse_cache().forget(&payload.uuid);
 
   Ok((StatusCode::OK, "Subscribe deleted successfully").into_response())
 }
@@ -178,158 +262,870 @@ pub struct SubscribeRefreshDto {
   pub uuid: String,
 }
 
+/// Result of a successful subscription refresh
+#[derive(Debug, Serialize)]
+pub struct SubscribeRefreshResultDto {
+  pub message: String,
+  pub format: SubscriptionFormat,
+  pub node_count: usize,
+  pub parse_summary: ParseSummary,
+}
+
+/// Default auto-refresh interval for subscriptions that don't set `refresh_interval_minutes`
+const DEFAULT_REFRESH_INTERVAL_MINUTES: u64 = 720;
+
+/// Where a subscription's content comes from
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SubscriptionSource {
+  /// Fetched from `subscription_url`
+  #[default]
+  Url,
+  /// Uploaded file
+  File,
+  /// Pasted text
+  Inline,
+}
+
+/// Longest accepted label
+const MAX_LABEL_LENGTH: usize = 32;
+
+/// Longest accepted notes text
+const MAX_NOTES_LENGTH: usize = 2000;
+
+/// Typed view of the subscription metadata stored in `SubscribeCreateDto::json`.
+/// Fields not listed here (pseudo_node_filter, ...) are read by their own modules.
 #[derive(Debug, Deserialize)]
-struct SubscriptionMetadata {
-  subscription_url: String,
-  #[allow(dead_code)]
-  website_url: Option<String>,
-  #[allow(dead_code)]
-  content: String,
-  #[allow(dead_code)]
-  last_updated: Option<String>,
-  #[allow(dead_code)]
-  enabled: Option<bool>,
+pub struct SubscriptionMetadata {
+  #[serde(default)]
+  pub subscription_url: String,
+  #[serde(default)]
+  pub source: SubscriptionSource,
+  pub website_url: Option<String>,
+  #[serde(default)]
+  pub content: String,
+  /// Time of the last successful fetch (RFC 3339)
+  pub last_updated: Option<String>,
+  /// Disabled subscriptions contribute no nodes anywhere
+  #[serde(default = "default_enabled")]
+  pub enabled: bool,
+  /// Higher priority wins when deduplicating nodes across subscriptions
+  #[serde(default)]
+  pub priority: i32,
+  #[serde(default)]
+  pub labels: Vec<String>,
+  pub notes: Option<String>,
+  /// Auto-refresh interval in minutes; 0 disables scheduled refresh
+  pub refresh_interval_minutes: Option<u64>,
+  /// Time of the last failed fetch (RFC 3339)
+  pub last_failure_at: Option<String>,
+  pub tag_template: Option<String>,
+  #[serde(default)]
+  pub tag_collision: TagCollisionStrategy,
+  #[serde(default)]
+  pub rename_rules: Vec<RenameRule>,
+  #[serde(flatten)]
+  pub fetch_options: FetchOptions,
+}
+
+impl SubscribeCreateDto {
+  /// Parse the typed subscription metadata
+  pub fn metadata(&self) -> Result<SubscriptionMetadata, AppError> {
+    serde_json::from_str(&self.json)
+      .map_err(|e| AppError::BadRequest(format!("Invalid subscription metadata: {}", e)))
+  }
+}
+
+/// Default User-Agent sent when a subscription doesn't set `user_agent`
+const DEFAULT_USER_AGENT: &str = "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/120.0.0.0 Safari/537.36";
+
+/// Default fetch timeout when a subscription doesn't set `timeout_seconds`
+const DEFAULT_FETCH_TIMEOUT_SECONDS: u64 = 30;
+
+/// Per-subscription HTTP options, stored as top-level fields of the subscription metadata
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct FetchOptions {
+  /// User-Agent header; providers often pick the response format from it (e.g. `sing-box`, `clash.meta`)
+  pub user_agent: Option<String>,
+  /// Extra request headers
+  #[serde(default)]
+  pub headers: HashMap<String, String>,
+  pub timeout_seconds: Option<u64>,
+  /// Proxy URL for the request: `http://`, `https://`, `socks5://` or `socks5h://`
+  pub proxy: Option<String>,
+}
+
+impl SubscriptionMetadata {
+  /// Check the user-editable fields before a subscription is saved
+  pub fn validate(&self) -> Result<(), AppError> {
+    let is_http_url = |url: &str| url.starts_with("http://") || url.starts_with("https://");
+
+    if self.source == SubscriptionSource::Url {
+      let url = self.subscription_url.trim();
+      if url.is_empty() {
+        return Err(AppError::BadRequest(
+          "subscription_url is required for URL subscriptions".to_string(),
+        ));
+      }
+      if !is_http_url(url) {
+        return Err(AppError::BadRequest(format!(
+          "subscription_url must be an http(s) URL: {}",
+          url
+        )));
+      }
+    }
+
+    if let Some(website_url) = self.website_url.as_deref().map(str::trim)
+      && !website_url.is_empty()
+      && !is_http_url(website_url)
+    {
+      return Err(AppError::BadRequest(format!(
+        "website_url must be an http(s) URL: {}",
+        website_url
+      )));
+    }
+
+    let mut seen = HashSet::new();
+    for label in &self.labels {
+      let label = label.trim();
+      if label.is_empty() {
+        return Err(AppError::BadRequest("Labels must not be empty".to_string()));
+      }
+      if label.chars().count() > MAX_LABEL_LENGTH {
+        return Err(AppError::BadRequest(format!(
+          "Label \"{}\" is longer than {} characters",
+          label, MAX_LABEL_LENGTH
+        )));
+      }
+      if !seen.insert(label.to_lowercase()) {
+        return Err(AppError::BadRequest(format!("Duplicate label: {}", label)));
+      }
+    }
+
+    if self
+      .notes
+      .as_ref()
+      .is_some_and(|notes| notes.chars().count() > MAX_NOTES_LENGTH)
+    {
+      return Err(AppError::BadRequest(format!(
+        "Notes are longer than {} characters",
+        MAX_NOTES_LENGTH
+      )));
+    }
+
+    if let Some(template) = &self.tag_template {
+      check_tag_template(template)?;
+    }
+    compile_rename_rules(&self.rename_rules)?;
+
+    Ok(())
+  }
+
+  /// Tag naming options of this subscription; `global_rules` run before its own rules
+  pub fn tag_naming(&self, global_rules: &[RenameRule]) -> TagNaming {
+    TagNaming {
+      template: self
+        .tag_template
+        .clone()
+        .filter(|t| !t.trim().is_empty())
+        .unwrap_or_else(|| DEFAULT_TAG_TEMPLATE.to_string()),
+      collision: self.tag_collision,
+      rename_rules: global_rules
+        .iter()
+        .chain(&self.rename_rules)
+        .cloned()
+        .collect(),
+    }
+  }
+
+  /// Check whether the scheduler should refresh this subscription at `now`.
+  /// The interval is measured from the last attempt, successful or not.
+  pub fn is_refresh_due(&self, now: chrono::DateTime<chrono::Utc>) -> bool {
+    if !self.enabled
+      || self.source != SubscriptionSource::Url
+      || self.subscription_url.trim().is_empty()
+    {
+      return false;
+    }
+
+    let interval = self
+      .refresh_interval_minutes
+      .unwrap_or(DEFAULT_REFRESH_INTERVAL_MINUTES);
+    if interval == 0 {
+      return false;
+    }
+
+    let parse_time = |t: &Option<String>| {
+      t.as_deref()
+        .and_then(|t| chrono::DateTime::parse_from_rfc3339(t).ok())
+        .map(|t| t.with_timezone(&chrono::Utc))
+    };
+    let last_attempt = parse_time(&self.last_updated).max(parse_time(&self.last_failure_at));
+
+    match last_attempt {
+      Some(last) => now - last >= chrono::Duration::minutes(interval as i64),
+      None => true,
+    }
+  }
 }
 
 pub async fn refresh_subscribe(
   axum::extract::Query(payload): axum::extract::Query<SubscribeRefreshDto>,
 ) -> Result<impl IntoResponse, AppError> {
-  let file_name = format!("{}.json", payload.uuid);
-  let dir_path = Path::new("./data/subscribes");
-  let file_path = dir_path.join(&file_name);
+  let parsed = refresh_subscription(&payload.uuid).await?;
 
-  if !file_path.exists() {
-    return Ok((StatusCode::NOT_FOUND, "Subscribe not found").into_response());
+  Ok(Json(SubscribeRefreshResultDto {
+    message: "Subscribe refreshed successfully".to_string(),
+    format: parsed.format,
+    node_count: parsed.outbounds.len(),
+    parse_summary: parsed.summary(),
+  }))
+}
+
+/// Fetch a subscription and store its content, returning the parsed result.
+/// On failure the cached content is kept and the error is recorded in the metadata.
+pub async fn refresh_subscription(uuid: &str) -> Result<ParsedSubscription, AppError> {
+  let file_path = Path::new("./data/subscribes").join(format!("{}.json", uuid));
+  let (parsed, content) = refresh_subscription_file(&file_path).await?;
+
+  if let Err(e) = subscribe_history::record_snapshot(uuid, &content).await {
+    log::warn!(
+      "Failed to record subscription history for {}: {:?}",
+      uuid,
+      e
+    );
   }
 
-  let content = fs::read_to_string(&file_path).await?;
-  let mut subscribe_dto: SubscribeCreateDto = serde_json::from_str(&content)?;
+  Ok(parsed)
+}
 
-  let metadata: SubscriptionMetadata = serde_json::from_str(&subscribe_dto.json)
-    .map_err(|e| AppError::from(anyhow::anyhow!("Invalid subscription metadata: {}", e)))?;
+/// Fetch a subscription file's remote source and store the new content in it.
+/// A failed fetch, or one that yields no nodes, only records the error; the previous content is kept.
+pub(crate) async fn refresh_subscription_file(
+  file_path: &Path,
+) -> Result<(ParsedSubscription, String), AppError> {
+  if !file_path.exists() {
+    return Err(AppError::NotFound("Subscribe not found".to_string()));
+  }
 
-  let client = reqwest::Client::builder()
-    .timeout(std::time::Duration::from_secs(30))
-    .build()
-    .map_err(|e| AppError::from(anyhow::anyhow!("Failed to create HTTP client: {}", e)))?;
+  let content = fs::read_to_string(file_path).await?;
+  let subscribe_dto: SubscribeCreateDto = serde_json::from_str(&content)?;
 
-  let response = client
-    .get(&metadata.subscription_url)
-    .header("User-Agent", "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/120.0.0.0 Safari/537.36")
-    .header("Accept", "*/*")
-    .send()
+  let metadata = subscribe_dto.metadata()?;
+  if metadata.source != SubscriptionSource::Url {
+    return Err(AppError::BadRequest(
+      "Subscription has no remote source; upload or paste new content instead".to_string(),
+    ));
+  }
+
+  // A response without a single node (an error page, an expired-token stub) counts as a failure
+  let fetch_result = fetch_subscription(&metadata.subscription_url, &metadata.fetch_options)
     .await
-    .map_err(|e| AppError::from(anyhow::anyhow!("Failed to fetch subscription: {}", e)))?;
+    .and_then(|fetched| {
+      let parsed = parse_subscription(&fetched.content);
+      if parsed.outbounds.is_empty() {
+        Err("No nodes found in fetched content".to_string())
+      } else {
+        Ok((fetched, parsed))
+      }
+    });
+  let now = chrono::Utc::now().to_rfc3339();
 
-  if !response.status().is_success() {
-    return Ok(
-      (
-        StatusCode::BAD_GATEWAY,
-        format!("Failed to fetch subscription: HTTP {}", response.status()),
-      )
-        .into_response(),
+  update_subscription_metadata(file_path, |metadata| match &fetch_result {
+    Ok((fetched, parsed)) => {
+      metadata.insert(
+        "content".to_string(),
+        serde_json::Value::String(fetched.content.clone()),
+      );
+      metadata.insert("last_updated".to_string(), serde_json::Value::String(now));
+      metadata.remove("last_error");
+      if let Ok(summary) = serde_json::to_value(parsed.summary()) {
+        metadata.insert("parse_summary".to_string(), summary);
+      }
+      // Drop stale userinfo if the provider stopped sending the header
+      match fetched
+        .userinfo
+        .as_ref()
+        .and_then(|u| serde_json::to_value(u).ok())
+      {
+        Some(userinfo) => {
+          metadata.insert("userinfo".to_string(), userinfo);
+        }
+        None => {
+          metadata.remove("userinfo");
+        }
+      }
+    }
+    Err(error) => {
+      metadata.insert(
+        "last_failure_at".to_string(),
+        serde_json::Value::String(now),
+      );
+      metadata.insert(
+        "last_error".to_string(),
+        serde_json::Value::String(error.clone()),
+      );
+    }
+  })
+  .await?;
+
+  fetch_result
+    .map(|(fetched, parsed)| (parsed, fetched.content))
+    .map_err(AppError::BadGateway)
+}
+
+/// Store uploaded or pasted content as a subscription's content.
+/// An existing subscription keeps its other settings; otherwise a new one named `name` is created.
+pub async fn import_subscription_content(
+  uuid: &str,
+  name: Option<&str>,
+  content: &str,
+  source: SubscriptionSource,
+  file_name: Option<&str>,
+) -> Result<ParsedSubscription, AppError> {
+  let parsed = parse_subscription(content);
+  if parsed.outbounds.is_empty() {
+    return Err(AppError::BadRequest(
+      "No nodes found in content".to_string(),
+    ));
+  }
+
+  let dir_path = Path::new("./data/subscribes");
+  let file_path = dir_path.join(format!("{}.json", uuid));
+  let summary = serde_json::to_value(parsed.summary())?;
+  let now = chrono::Utc::now().to_rfc3339();
+  let apply = |metadata: &mut serde_json::Map<String, serde_json::Value>| {
+    metadata.insert(
+      "content".to_string(),
+      serde_json::Value::String(content.to_string()),
     );
+    metadata.insert(
+      "source".to_string(),
+      serde_json::to_value(source).unwrap_or_default(),
+    );
+    match file_name {
+      Some(file_name) => {
+        metadata.insert(
+          "file_name".to_string(),
+          serde_json::Value::String(file_name.to_string()),
+        );
+      }
+      None => {
+        metadata.remove("file_name");
+      }
+    }
+    metadata.insert("last_updated".to_string(), serde_json::Value::String(now));
+    metadata.insert("parse_summary".to_string(), summary);
+    metadata.remove("last_error");
+    metadata.remove("userinfo");
+  };
+
+  if file_path.exists() {
+    update_subscription_metadata(&file_path, apply).await?;
+  } else {
+    let name = name
+      .map(str::trim)
+      .filter(|n| !n.is_empty())
+      .ok_or_else(|| AppError::BadRequest("Name is required".to_string()))?;
+    let mut metadata = serde_json::Map::new();
+    metadata.insert(
+      "subscription_url".to_string(),
+      serde_json::Value::String(String::new()),
+    );
+    metadata.insert("enabled".to_string(), serde_json::Value::Bool(true));
+    apply(&mut metadata);
+
+    if !dir_path.exists() {
+      fs::create_dir_all(dir_path).await?;
+    }
+    let subscribe = SubscribeCreateDto {
+      uuid: uuid.to_string(),
+      name: name.to_string(),
+      json: serde_json::to_string(&metadata)?,
+    };
+    fs::write(&file_path, serde_json::to_string(&subscribe)?.as_bytes()).await?;
   }
 
-  let subscription_content = response.text().await.map_err(|e| {
-    AppError::from(anyhow::anyhow!(
-      "Failed to read subscription content: {}",
+  if let Err(e) = subscribe_history::record_snapshot(uuid, content).await {
+    log::warn!(
+      "Failed to record subscription history for {}: {:?}",
+      uuid,
       e
-    ))
-  })?;
+    );
+  }
 
-  let now = chrono::Utc::now().to_rfc3339();
+  Ok(parsed)
+}
 
-  let updated_metadata = serde_json::json!({
-    "subscription_url": metadata.subscription_url,
-    "website_url": metadata.website_url,
-    "content": subscription_content,
-    "last_updated": now,
-    "enabled": metadata.enabled.unwrap_or(true),
-  });
+/// Upload a subscription file (multipart fields `uuid`, `name` and `file`)
+pub async fn upload_subscribe(mut multipart: Multipart) -> Result<impl IntoResponse, AppError> {
+  let mut uuid = None;
+  let mut name = None;
+  let mut file = None;
+  while let Ok(Some(field)) = multipart.next_field().await {
+    match field.name() {
+      Some("uuid") => uuid = field.text().await.ok(),
+      Some("name") => name = field.text().await.ok(),
+      Some("file") => {
+        let file_name = field.file_name().map(str::to_string);
+        let data = field
+          .bytes()
+          .await
+          .map_err(|e| AppError::BadRequest(format!("Failed to read file: {}", e)))?;
+        file = Some((file_name, data));
+      }
+      _ => {}
+    }
+  }
 
-  subscribe_dto.json = serde_json::to_string(&updated_metadata)?;
+  let uuid = uuid.ok_or_else(|| AppError::BadRequest("No uuid field found".to_string()))?;
+  let (file_name, data) =
+    file.ok_or_else(|| AppError::BadRequest("No file field found".to_string()))?;
+  let content = String::from_utf8(data.to_vec())
+    .map_err(|_| AppError::BadRequest("File is not valid UTF-8 text".to_string()))?;
 
-  fs::write(file_path, serde_json::to_string(&subscribe_dto)?.as_bytes()).await?;
+  let parsed = import_subscription_content(
+    &uuid,
+    name.as_deref(),
+    &content,
+    SubscriptionSource::File,
+    file_name.as_deref(),
+  )
+  .await?;
 
-  Ok((StatusCode::OK, "Subscribe refreshed successfully").into_response())
+  Ok(Json(SubscribeRefreshResultDto {
+    message: "Subscription file uploaded successfully".to_string(),
+    format: parsed.format,
+    node_count: parsed.outbounds.len(),
+    parse_summary: parsed.summary(),
+  }))
 }
 
 #[derive(Debug, Deserialize)]
-pub struct SubscribeOutboundsDto {
+pub struct SubscribePasteDto {
   pub uuid: String,
+  /// Required when creating a new subscription
+  pub name: Option<String>,
+  pub content: String,
 }
 
-/// Get all outbounds for a specific subscription
-pub async fn get_subscribe_outbounds(
-  axum::extract::Query(payload): axum::extract::Query<SubscribeOutboundsDto>,
+/// Store pasted share links or a subscription body as a subscription's content
+pub async fn paste_subscribe(
+  Json(payload): Json<SubscribePasteDto>,
 ) -> Result<impl IntoResponse, AppError> {
-  let file_name = format!("{}.json", payload.uuid);
-  let dir_path = Path::new("./data/subscribes");
-  let file_path = dir_path.join(&file_name);
+  let parsed = import_subscription_content(
+    &payload.uuid,
+    payload.name.as_deref(),
+    &payload.content,
+    SubscriptionSource::Inline,
+    None,
+  )
+  .await?;
 
-  if !file_path.exists() {
-    return Ok((StatusCode::NOT_FOUND, Json(Vec::<serde_json::Value>::new())).into_response());
+  Ok(Json(SubscribeRefreshResultDto {
+    message: "Subscription content saved successfully".to_string(),
+    format: parsed.format,
+    node_count: parsed.outbounds.len(),
+    parse_summary: parsed.summary(),
+  }))
+}
+
+/// Subscription content and response metadata from a successful fetch
+pub struct FetchedSubscription {
+  pub content: String,
+  pub userinfo: Option<SubscriptionUserinfo>,
+}
+
+/// Download subscription content, returning a readable error message on failure
+pub async fn fetch_subscription(
+  url: &str,
+  options: &FetchOptions,
+) -> Result<FetchedSubscription, String> {
+  let timeout = options
+    .timeout_seconds
+    .filter(|&secs| secs > 0)
+    .unwrap_or(DEFAULT_FETCH_TIMEOUT_SECONDS);
+  let mut builder = reqwest::Client::builder().timeout(std::time::Duration::from_secs(timeout));
+  if let Some(proxy_url) = options.proxy.as_deref().map(str::trim)
+    && !proxy_url.is_empty()
+  {
+    let proxy = reqwest::Proxy::all(proxy_url)
+      .map_err(|e| format!("Invalid proxy URL {}: {}", proxy_url, e))?;
+    builder = builder.proxy(proxy);
   }
+  let client = builder
+    .build()
+    .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
 
-  let content = fs::read_to_string(&file_path).await?;
-  let subscribe_dto: SubscribeCreateDto = serde_json::from_str(&content)?;
+  let user_agent = options
+    .user_agent
+    .as_deref()
+    .filter(|ua| !ua.trim().is_empty())
+    .unwrap_or(DEFAULT_USER_AGENT);
+  let mut headers = reqwest::header::HeaderMap::new();
+  headers.insert(
+    reqwest::header::USER_AGENT,
+    reqwest::header::HeaderValue::from_str(user_agent)
+      .map_err(|_| format!("Invalid User-Agent: {}", user_agent))?,
+  );
+  headers.insert(
+    reqwest::header::ACCEPT,
+    reqwest::header::HeaderValue::from_static("*/*"),
+  );
+  // Extra headers replace the defaults above when they share a name
+  for (name, value) in &options.headers {
+    let header_name = reqwest::header::HeaderName::from_bytes(name.as_bytes())
+      .map_err(|_| format!("Invalid header name: {}", name))?;
+    let header_value = reqwest::header::HeaderValue::from_str(value)
+      .map_err(|_| format!("Invalid value for header {}", name))?;
+    headers.insert(header_name, header_value);
+  }
 
-  // Parse subscription metadata
-  let metadata: serde_json::Value = serde_json::from_str(&subscribe_dto.json)
-    .map_err(|e| AppError::from(anyhow::anyhow!("Invalid subscription metadata: {}", e)))?;
+  let request = client.get(url).headers(headers);
+  let response = request
+    .send()
+    .await
+    .map_err(|e| format!("Failed to fetch subscription: {}", e))?;
+
+  if !response.status().is_success() {
+    return Err(format!(
+      "Failed to fetch subscription: HTTP {}",
+      response.status()
+    ));
+  }
 
-  // Get content and decode
-  let outbounds = if let Some(content_str) = metadata.get("content").and_then(|c| c.as_str()) {
-    if let Some(decoded_str) = decode_base64_content(content_str) {
-      parse_subscription_content(&decoded_str)?
-    } else {
-      Vec::new()
+  let userinfo = response
+    .headers()
+    .get("subscription-userinfo")
+    .and_then(|v| v.to_str().ok())
+    .and_then(parse_subscription_userinfo);
+
+  let content = response
+    .text()
+    .await
+    .map_err(|e| format!("Failed to read subscription content: {}", e))?;
+
+  Ok(FetchedSubscription { content, userinfo })
+}
+
+/// Traffic quota and expiry from the `Subscription-Userinfo` response header (bytes / unix seconds)
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SubscriptionUserinfo {
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub upload: Option<u64>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub download: Option<u64>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub total: Option<u64>,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub expire: Option<i64>,
+}
+
+/// Parse a `Subscription-Userinfo` header value,
+/// e.g. "upload=455727941; download=6174315083; total=1073741824000; expire=1671815872".
+/// Returns None if no known field is present.
+pub fn parse_subscription_userinfo(header: &str) -> Option<SubscriptionUserinfo> {
+  let mut userinfo = SubscriptionUserinfo::default();
+
+  for pair in header.split(';') {
+    let Some((key, value)) = pair.split_once('=') else {
+      continue;
+    };
+    // Some providers send floats (e.g. "1.2e10"); truncate to whole bytes
+    let Ok(number) = value.trim().parse::<f64>() else {
+      continue;
+    };
+    if number < 0.0 {
+      continue;
     }
+
+    match key.trim().to_ascii_lowercase().as_str() {
+      "upload" => userinfo.upload = Some(number as u64),
+      "download" => userinfo.download = Some(number as u64),
+      "total" => userinfo.total = Some(number as u64),
+      // expire=0 means "never expires"
+      "expire" if number > 0.0 => userinfo.expire = Some(number as i64),
+      _ => {}
+    }
+  }
+
+  if userinfo == SubscriptionUserinfo::default() {
+    None
   } else {
-    Vec::new()
+    Some(userinfo)
+  }
+}
+
+/// Remaining quota ratio below which a subscription is reported as "low"
+const LOW_QUOTA_RATIO: f64 = 0.1;
+/// Days before expiry at which a subscription is reported as "expiring"
+const EXPIRING_SOON_DAYS: i64 = 7;
+
+#[derive(Debug, Serialize)]
+pub struct SubscriptionUsageDto {
+  pub uuid: String,
+  pub name: String,
+  pub upload: Option<u64>,
+  pub download: Option<u64>,
+  pub total: Option<u64>,
+  /// upload + download
+  pub used: Option<u64>,
+  pub remaining: Option<u64>,
+  /// Used share of the total quota, 0-100
+  pub used_percent: Option<f64>,
+  /// Expiry time (RFC 3339)
+  pub expire_at: Option<String>,
+  pub days_until_expire: Option<i64>,
+  /// "unknown", "ok", "low", "expiring", "exhausted" or "expired"
+  pub status: String,
+}
+
+/// Build the quota/expiry summary for one subscription
+pub fn summarize_usage(
+  uuid: String,
+  name: String,
+  userinfo: Option<&SubscriptionUserinfo>,
+  now: chrono::DateTime<chrono::Utc>,
+) -> SubscriptionUsageDto {
+  let Some(info) = userinfo else {
+    return SubscriptionUsageDto {
+      uuid,
+      name,
+      upload: None,
+      download: None,
+      total: None,
+      used: None,
+      remaining: None,
+      used_percent: None,
+      expire_at: None,
+      days_until_expire: None,
+      status: "unknown".to_string(),
+    };
+  };
+
+  let used = match (info.upload, info.download) {
+    (None, None) => None,
+    (upload, download) => Some(upload.unwrap_or(0) + download.unwrap_or(0)),
+  };
+  // A total of 0 means the provider does not meter traffic
+  let total = info.total.filter(|t| *t > 0);
+  let remaining = total.map(|t| t.saturating_sub(used.unwrap_or(0)));
+  let used_percent = total.map(|t| (used.unwrap_or(0) as f64 / t as f64 * 100.0).min(100.0));
+
+  let expire_at = info
+    .expire
+    .and_then(|e| chrono::DateTime::from_timestamp(e, 0));
+  let days_until_expire = expire_at.map(|e| (e - now).num_days());
+
+  let status = if expire_at.is_some_and(|e| e <= now) {
+    "expired"
+  } else if remaining == Some(0) {
+    "exhausted"
+  } else if days_until_expire.is_some_and(|d| d < EXPIRING_SOON_DAYS) {
+    "expiring"
+  } else if used_percent.is_some_and(|p| p >= (1.0 - LOW_QUOTA_RATIO) * 100.0) {
+    "low"
+  } else {
+    "ok"
   };
 
-  Ok((StatusCode::OK, Json(outbounds)).into_response())
+  SubscriptionUsageDto {
+    uuid,
+    name,
+    upload: info.upload,
+    download: info.download,
+    total: info.total,
+    used,
+    remaining,
+    used_percent,
+    expire_at: expire_at.map(|e| e.to_rfc3339()),
+    days_until_expire,
+    status: status.to_string(),
+  }
 }
 
-/// Decode base64-encoded subscription content (handles padded and unpadded base64)
-fn decode_base64_content(content: &str) -> Option<String> {
-  let content = content.trim();
-  if content.is_empty() {
-    return None;
+/// Sort priority of a usage status (most urgent first)
+fn usage_status_rank(status: &str) -> u8 {
+  match status {
+    "expired" => 0,
+    "exhausted" => 1,
+    "expiring" => 2,
+    "low" => 3,
+    "ok" => 4,
+    _ => 5,
   }
-  // Try standard base64 (with padding) first
-  if let Ok(decoded) = base64::Engine::decode(&base64::engine::general_purpose::STANDARD, content) {
-    return String::from_utf8(decoded).ok();
+}
+
+/// Quota/expiry summary of all subscriptions, most urgent first
+pub async fn get_subscribe_usage() -> Result<impl IntoResponse, AppError> {
+  let now = chrono::Utc::now();
+  let mut usages: Vec<SubscriptionUsageDto> = load_subscribes_in_order()
+    .await?
+    .into_iter()
+    .map(|subscribe| {
+      let userinfo = read_userinfo(&subscribe.json);
+      summarize_usage(subscribe.uuid, subscribe.name, userinfo.as_ref(), now)
+    })
+    .collect();
+
+  // Stable sort keeps subscription order within the same status
+  usages.sort_by_key(|u| usage_status_rank(&u.status));
+
+  Ok(Json(usages))
+}
+
+/// Re-read a subscription file and apply `update` to its metadata object.
+/// The file is read again (rather than reusing an earlier copy) so that edits made
+/// while a fetch was in flight are not overwritten.
+async fn update_subscription_metadata<F>(file_path: &Path, update: F) -> Result<(), AppError>
+where
+  F: FnOnce(&mut serde_json::Map<String, serde_json::Value>),
+{
+  if !file_path.exists() {
+    return Err(AppError::NotFound("Subscribe not found".to_string()));
   }
-  // Fall back to base64 without padding requirement
-  let decoded =
-    base64::Engine::decode(&base64::engine::general_purpose::STANDARD_NO_PAD, content).ok()?;
-  String::from_utf8(decoded).ok()
+
+  let content = fs::read_to_string(file_path).await?;
+  let mut subscribe_dto: SubscribeCreateDto = serde_json::from_str(&content)?;
+
+  let mut metadata: serde_json::Map<String, serde_json::Value> =
+    serde_json::from_str(&subscribe_dto.json)
+      .map_err(|e| AppError::from(anyhow::anyhow!("Invalid subscription metadata: {}", e)))?;
+  update(&mut metadata);
+
+  subscribe_dto.json = serde_json::to_string(&metadata)?;
+  fs::write(file_path, serde_json::to_string(&subscribe_dto)?.as_bytes()).await?;
+
+  Ok(())
 }
 
-/// Parse subscription content to outbound objects
-fn parse_subscription_content(decoded_str: &str) -> Result<Vec<serde_json::Value>, AppError> {
-  let mut outbounds = Vec::new();
+/// Refresh every enabled subscription whose auto-refresh interval has elapsed.
+/// Failures are recorded on the subscription and logged; they never abort the sweep.
+pub async fn refresh_due_subscriptions() -> Result<(), AppError> {
+  let dir_path = Path::new("./data/subscribes");
+  if !dir_path.exists() {
+    return Ok(());
+  }
+
+  let now = chrono::Utc::now();
+  let mut entries = fs::read_dir(dir_path).await?;
 
-  for line in decoded_str.lines() {
-    let line = line.trim();
-    if line.is_empty() {
+  while let Some(entry) = entries.next_entry().await? {
+    let path = entry.path();
+    if path.extension().and_then(|s| s.to_str()) != Some("json") {
       continue;
     }
 
-    // Parse different formats and convert to sing-box outbound
-    if let Ok(outbound) = parse_subscription_line(line) {
-      outbounds.push(outbound);
+    let Ok(content) = fs::read_to_string(&path).await else {
+      continue;
+    };
+    let Ok(subscribe) = serde_json::from_str::<SubscribeCreateDto>(&content) else {
+      continue;
+    };
+    let Ok(metadata) = serde_json::from_str::<SubscriptionMetadata>(&subscribe.json) else {
+      continue;
+    };
+
+    if !metadata.is_refresh_due(now) {
+      continue;
+    }
+
+    match refresh_subscription(&subscribe.uuid).await {
+      Ok(parsed) => log::info!(
+        "Auto-refreshed subscription {}: {} nodes ({:?})",
+        subscribe.name,
+        parsed.outbounds.len(),
+        parsed.format
+      ),
+      Err(e) => log::warn!(
+        "Failed to auto-refresh subscription {}: {:?}",
+        subscribe.name,
+        e
+      ),
     }
   }
 
-  Ok(outbounds)
+  Ok(())
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SubscribeOutboundsDto {
+  pub uuid: String,
+}
+
+/// Parsed outbounds of a subscription, with the lines that failed to parse
+#[derive(Debug, Serialize)]
+pub struct SubscribeOutboundsResultDto {
+  /// Disabled subscriptions list no outbounds
+  pub enabled: bool,
+  pub format: SubscriptionFormat,
+  pub outbounds: Vec<serde_json::Value>,
+  pub diagnostics: Vec<ParseDiagnostic>,
+  pub summary: ParseSummary,
+  /// Informational pseudo-nodes dropped before naming
+  pub excluded: Vec<ExcludedNode>,
+}
+
+/// Outbounds of a single subscription, named and patched as the config generator does
+/// (tags may still differ there when they collide with another subscription)
+pub async fn preview_subscription(
+  subscribe: &SubscribeCreateDto,
+) -> Result<SubscribeOutboundsResultDto, AppError> {
+  let metadata: serde_json::Value = serde_json::from_str(&subscribe.json)
+    .map_err(|e| AppError::from(anyhow::anyhow!("Invalid subscription metadata: {}", e)))?;
+  let typed = subscribe.metadata()?;
+
+  // Parse content (base64 URI list, Clash YAML or sing-box JSON)
+  let parsed = parse_subscription(&typed.content);
+  let summary = parsed.summary();
+  let (nodes, excluded) = exclude_pseudo_nodes(
+    parsed.outbounds,
+    &PseudoNodeFilter::from_metadata(&metadata),
+  );
+
+  let outbounds = if typed.enabled {
+    let global_rules = load_global_rename_rules().await?;
+    let naming = typed.tag_naming(&global_rules);
+    let mut outbounds = apply_tag_naming(nodes, &subscribe.name, &naming, &mut HashSet::new())?;
+    apply_node_overrides(
+      &mut outbounds,
+      &subscribe.uuid,
+      &load_node_overrides().await?,
+    );
+    outbounds
+  } else {
+    Vec::new()
+  };
+
+  Ok(SubscribeOutboundsResultDto {
+    enabled: typed.enabled,
+    format: parsed.format,
+    summary,
+    outbounds,
+    diagnostics: parsed.diagnostics,
+    excluded,
+  })
+}
+
+/// Get all outbounds for a specific subscription
+pub async fn get_subscribe_outbounds(
+  axum::extract::Query(payload): axum::extract::Query<SubscribeOutboundsDto>,
+) -> Result<impl IntoResponse, AppError> {
+  let file_path = Path::new("./data/subscribes").join(format!("{}.json", payload.uuid));
+  if !file_path.exists() {
+    return Err(AppError::NotFound("Subscribe not found".to_string()));
+  }
+
+  let content = fs::read_to_string(&file_path).await?;
+  let subscribe_dto: SubscribeCreateDto = serde_json::from_str(&content)?;
+
+  Ok(Json(preview_subscription(&subscribe_dto).await?))
 }
 
 // Use subscription parser from shared module
-use crate::backend::subscription_parser::parse_subscription_line;
+use crate::backend::subscription_parser::{
+  ParseDiagnostic, ParseSummary, ParsedSubscription, SubscriptionFormat, parse_subscription,
+};
 
 #[derive(Debug, Deserialize)]
 pub struct ReorderDto {
@@ -0,0 +1,310 @@
+use axum::{Json, response::IntoResponse};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashSet;
+use std::path::PathBuf;
+use tokio::fs;
+
+use crate::backend::error::AppError;
+use crate::backend::subscription_parser::{
+  SubscriptionFormat, parse_subscription, parse_subscription_content,
+};
+
+/// Number of fetched contents kept per subscription
+const SUBSCRIPTION_HISTORY_LIMIT: usize = 10;
+
+/// Snapshots live in `./data/subscribes/.history/<uuid>/<id>.json`
+fn history_dir(uuid: &str) -> PathBuf {
+  PathBuf::from("./data/subscribes/.history").join(uuid)
+}
+
+/// One fetched subscription content
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SubscriptionSnapshot {
+  /// Sortable snapshot id derived from the fetch time
+  pub id: String,
+  /// Fetch time (RFC 3339)
+  pub fetched_at: String,
+  pub content: String,
+}
+
+/// Snapshot listing entry (content omitted)
+#[derive(Debug, Serialize)]
+pub struct SnapshotSummaryDto {
+  pub id: String,
+  pub fetched_at: String,
+  pub format: SubscriptionFormat,
+  pub node_count: usize,
+}
+
+/// Record a fetched content for `uuid`, pruning the oldest snapshots beyond the limit.
+/// Content identical to the latest snapshot is not stored again.
+pub async fn record_snapshot(uuid: &str, content: &str) -> Result<(), AppError> {
+  let snapshots = load_snapshots(uuid).await?;
+  if snapshots.last().is_some_and(|s| s.content == content) {
+    return Ok(());
+  }
+
+  let dir = history_dir(uuid);
+  fs::create_dir_all(&dir).await?;
+
+  let now = chrono::Utc::now();
+  let snapshot = SubscriptionSnapshot {
+    id: now.format("%Y%m%dT%H%M%S%3fZ").to_string(),
+    fetched_at: now.to_rfc3339(),
+    content: content.to_string(),
+  };
+  fs::write(
+    dir.join(format!("{}.json", snapshot.id)),
+    serde_json::to_string(&snapshot)?.as_bytes(),
+  )
+  .await?;
+
+  let excess = (snapshots.len() + 1).saturating_sub(SUBSCRIPTION_HISTORY_LIMIT);
+  for old in snapshots.iter().take(excess) {
+    fs::remove_file(dir.join(format!("{}.json", old.id))).await?;
+  }
+
+  Ok(())
+}
+
+/// Remove all snapshots of a deleted subscription
+pub async fn delete_history(uuid: &str) -> Result<(), AppError> {
+  let dir = history_dir(uuid);
+  if dir.exists() {
+    fs::remove_dir_all(dir).await?;
+  }
+  Ok(())
+}
+
+/// Load all snapshots of a subscription, oldest first
+async fn load_snapshots(uuid: &str) -> Result<Vec<SubscriptionSnapshot>, AppError> {
+  let dir = history_dir(uuid);
+  if !dir.exists() {
+    return Ok(Vec::new());
+  }
+
+  let mut snapshots = Vec::new();
+  let mut entries = fs::read_dir(&dir).await?;
+  while let Some(entry) = entries.next_entry().await? {
+    let path = entry.path();
+    if path.extension().and_then(|s| s.to_str()) != Some("json") {
+      continue;
+    }
+    let content = fs::read_to_string(&path).await?;
+    if let Ok(snapshot) = serde_json::from_str::<SubscriptionSnapshot>(&content) {
+      snapshots.push(snapshot);
+    }
+  }
+
+  snapshots.sort_by(|a, b| a.id.cmp(&b.id));
+  Ok(snapshots)
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SubscribeHistoryDto {
+  pub uuid: String,
+}
+
+/// List the stored snapshots of a subscription, newest first
+pub async fn list_subscribe_history(
+  axum::extract::Query(payload): axum::extract::Query<SubscribeHistoryDto>,
+) -> Result<impl IntoResponse, AppError> {
+  let summaries: Vec<SnapshotSummaryDto> = load_snapshots(&payload.uuid)
+    .await?
+    .into_iter()
+    .rev()
+    .map(|snapshot| {
+      let parsed = parse_subscription(&snapshot.content);
+      SnapshotSummaryDto {
+        id: snapshot.id,
+        fetched_at: snapshot.fetched_at,
+        format: parsed.format,
+        node_count: parsed.outbounds.len(),
+      }
+    })
+    .collect();
+
+  Ok(Json(summaries))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SubscribeDiffDto {
+  pub uuid: String,
+  /// Older snapshot id; defaults to the snapshot before `to`
+  pub from: Option<String>,
+  /// Newer snapshot id; defaults to the latest snapshot
+  pub to: Option<String>,
+}
+
+/// Identifying fields of a node in a diff
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct NodeSummary {
+  pub tag: String,
+  #[serde(rename = "type")]
+  pub node_type: String,
+  pub server: String,
+  pub server_port: Option<u64>,
+}
+
+/// A node present in both snapshots whose settings differ
+#[derive(Debug, Serialize)]
+pub struct ChangedNode {
+  pub before: NodeSummary,
+  pub after: NodeSummary,
+  /// Top-level outbound fields whose values differ
+  pub fields: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SubscriptionDiffDto {
+  pub from: String,
+  pub to: String,
+  pub added: Vec<NodeSummary>,
+  pub removed: Vec<NodeSummary>,
+  pub changed: Vec<ChangedNode>,
+  pub unchanged: usize,
+}
+
+/// Return the node diff between two snapshots of a subscription
+pub async fn get_subscribe_diff(
+  axum::extract::Query(payload): axum::extract::Query<SubscribeDiffDto>,
+) -> Result<impl IntoResponse, AppError> {
+  let snapshots = load_snapshots(&payload.uuid).await?;
+  let find = |id: &str| {
+    snapshots
+      .iter()
+      .position(|s| s.id == id)
+      .ok_or_else(|| AppError::NotFound(format!("Snapshot not found: {}", id)))
+  };
+
+  let to_index = match payload.to.as_deref() {
+    Some(id) => find(id)?,
+    None => snapshots
+      .len()
+      .checked_sub(1)
+      .ok_or_else(|| AppError::NotFound("Subscription has no history".to_string()))?,
+  };
+  let from_index = match payload.from.as_deref() {
+    Some(id) => find(id)?,
+    None => to_index
+      .checked_sub(1)
+      .ok_or_else(|| AppError::BadRequest("No earlier snapshot to compare against".to_string()))?,
+  };
+
+  let from = &snapshots[from_index];
+  let to = &snapshots[to_index];
+  let diff = diff_nodes(
+    &parse_subscription_content(&from.content),
+    &parse_subscription_content(&to.content),
+  );
+
+  Ok(Json(SubscriptionDiffDto {
+    from: from.id.clone(),
+    to: to.id.clone(),
+    added: diff.added,
+    removed: diff.removed,
+    changed: diff.changed,
+    unchanged: diff.unchanged,
+  }))
+}
+
+/// Difference between two lists of parsed outbounds
+#[derive(Debug, Default)]
+pub struct NodeDiff {
+  pub added: Vec<NodeSummary>,
+  pub removed: Vec<NodeSummary>,
+  pub changed: Vec<ChangedNode>,
+  pub unchanged: usize,
+}
+
+fn summarize_node(outbound: &Value) -> NodeSummary {
+  let text = |key: &str| {
+    outbound
+      .get(key)
+      .and_then(|v| v.as_str())
+      .unwrap_or_default()
+      .to_string()
+  };
+  NodeSummary {
+    tag: text("tag"),
+    node_type: text("type"),
+    server: text("server"),
+    server_port: outbound.get("server_port").and_then(|v| v.as_u64()),
+  }
+}
+
+fn changed_fields(before: &Value, after: &Value) -> Vec<String> {
+  let empty = serde_json::Map::new();
+  let before = before.as_object().unwrap_or(&empty);
+  let after = after.as_object().unwrap_or(&empty);
+
+  let mut fields: Vec<String> = before
+    .keys()
+    .chain(after.keys())
+    .filter(|key| before.get(*key) != after.get(*key))
+    .cloned()
+    .collect::<HashSet<_>>()
+    .into_iter()
+    .collect();
+  fields.sort();
+  fields
+}
+
+/// Add a matched pair to the diff, as unchanged or changed
+fn record_match(diff: &mut NodeDiff, old: &Value, new: &Value) {
+  let fields = changed_fields(old, new);
+  if fields.is_empty() {
+    diff.unchanged += 1;
+  } else {
+    diff.changed.push(ChangedNode {
+      before: summarize_node(old),
+      after: summarize_node(new),
+      fields,
+    });
+  }
+}
+
+/// Diff two node lists. Nodes are matched by tag first; the leftovers are then matched by
+/// `server:port`, so a renamed node shows up as changed rather than removed + added.
+pub fn diff_nodes(before: &[Value], after: &[Value]) -> NodeDiff {
+  let after_summaries: Vec<NodeSummary> = after.iter().map(summarize_node).collect();
+  let mut matched = vec![false; after.len()];
+  let mut diff = NodeDiff::default();
+  let mut unmatched_before = Vec::new();
+
+  for old in before {
+    let tag = summarize_node(old).tag;
+    match (0..after.len()).find(|&i| !matched[i] && after_summaries[i].tag == tag) {
+      Some(i) => {
+        matched[i] = true;
+        record_match(&mut diff, old, &after[i]);
+      }
+      None => unmatched_before.push(old),
+    }
+  }
+
+  for old in unmatched_before {
+    let summary = summarize_node(old);
+    let same_endpoint = |i: &usize| {
+      !matched[*i]
+        && after_summaries[*i].server == summary.server
+        && after_summaries[*i].server_port == summary.server_port
+    };
+    match (0..after.len()).find(same_endpoint) {
+      Some(i) => {
+        matched[i] = true;
+        record_match(&mut diff, old, &after[i]);
+      }
+      None => diff.removed.push(summary),
+    }
+  }
+
+  diff.added = after_summaries
+    .into_iter()
+    .zip(matched)
+    .filter(|(_, matched)| !matched)
+    .map(|(summary, _)| summary)
+    .collect();
+  diff
+}
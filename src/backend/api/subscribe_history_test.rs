@@ -0,0 +1,97 @@
+#[cfg(test)]
+mod tests {
+  use super::super::subscribe_history::diff_nodes;
+  use crate::backend::subscription_parser::parse_subscription_content;
+  use serde_json::json;
+
+  fn node(tag: &str, server: &str, port: u64, password: &str) -> serde_json::Value {
+    json!({
+      "type": "trojan",
+      "tag": tag,
+      "server": server,
+      "server_port": port,
+      "password": password
+    })
+  }
+
+  #[test]
+  fn test_diff_identical_lists() {
+    let nodes = vec![
+      node("A", "a.example.com", 443, "p"),
+      node("B", "b.example.com", 443, "p"),
+    ];
+    let diff = diff_nodes(&nodes, &nodes);
+
+    assert!(diff.added.is_empty());
+    assert!(diff.removed.is_empty());
+    assert!(diff.changed.is_empty());
+    assert_eq!(diff.unchanged, 2);
+  }
+
+  #[test]
+  fn test_diff_added_removed_changed() {
+    let before = vec![
+      node("HK-01", "hk.example.com", 443, "p"),
+      node("JP-01", "jp.example.com", 443, "p"),
+      node("US-01", "us.example.com", 443, "p"),
+    ];
+    let after = vec![
+      node("HK-01", "hk.example.com", 8443, "p"),
+      node("JP-01", "jp.example.com", 443, "p"),
+      node("SG-01", "sg.example.com", 443, "p"),
+    ];
+    let diff = diff_nodes(&before, &after);
+
+    assert_eq!(diff.unchanged, 1);
+    assert_eq!(diff.added.len(), 1);
+    assert_eq!(diff.added[0].tag, "SG-01");
+    assert_eq!(diff.removed.len(), 1);
+    assert_eq!(diff.removed[0].tag, "US-01");
+    assert_eq!(diff.changed.len(), 1);
+    assert_eq!(diff.changed[0].before.server_port, Some(443));
+    assert_eq!(diff.changed[0].after.server_port, Some(8443));
+    assert_eq!(diff.changed[0].fields, vec!["server_port"]);
+  }
+
+  #[test]
+  fn test_diff_matches_renamed_node_by_endpoint() {
+    let before = vec![node("HK-01", "hk.example.com", 443, "p")];
+    let after = vec![node("Hong Kong 01", "hk.example.com", 443, "p")];
+    let diff = diff_nodes(&before, &after);
+
+    assert!(diff.added.is_empty());
+    assert!(diff.removed.is_empty());
+    assert_eq!(diff.changed.len(), 1);
+    assert_eq!(diff.changed[0].before.tag, "HK-01");
+    assert_eq!(diff.changed[0].after.tag, "Hong Kong 01");
+    assert_eq!(diff.changed[0].fields, vec!["tag"]);
+  }
+
+  #[test]
+  fn test_diff_duplicate_tags() {
+    let before = vec![node("Node", "a.example.com", 443, "p")];
+    let after = vec![
+      node("Node", "a.example.com", 443, "p"),
+      node("Node", "b.example.com", 443, "p"),
+    ];
+    let diff = diff_nodes(&before, &after);
+
+    assert_eq!(diff.unchanged, 1);
+    assert_eq!(diff.added.len(), 1);
+    assert_eq!(diff.added[0].server, "b.example.com");
+  }
+
+  #[test]
+  fn test_diff_parsed_subscription_contents() {
+    let before = parse_subscription_content(
+      "trojan://old@a.example.com:443#A\ntrojan://p@b.example.com:443#B",
+    );
+    let after = parse_subscription_content("trojan://new@a.example.com:443#A");
+    let diff = diff_nodes(&before, &after);
+
+    assert_eq!(diff.removed.len(), 1);
+    assert_eq!(diff.removed[0].tag, "B");
+    assert_eq!(diff.changed.len(), 1);
+    assert_eq!(diff.changed[0].fields, vec!["password"]);
+  }
+}
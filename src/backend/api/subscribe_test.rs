@@ -0,0 +1,514 @@
+#[cfg(test)]
+mod tests {
+  use super::super::subscribe::{
+    FetchOptions, SubscribeCreateDto, SubscriptionMetadata, SubscriptionSource,
+    SubscriptionUserinfo, fetch_subscription, merge_editable_metadata, parse_subscription_userinfo,
+    refresh_subscription_file, summarize_usage,
+  };
+  use crate::backend::error::AppError;
+  use crate::backend::tag_template::{
+    DEFAULT_TAG_TEMPLATE, RenameRule, TagCollisionStrategy, TagNaming,
+  };
+  use axum::http::HeaderMap;
+  use chrono::{DateTime, Duration, Utc};
+  use std::collections::HashMap;
+
+  fn metadata(json: &str) -> SubscriptionMetadata {
+    serde_json::from_str(json).expect("Invalid test metadata")
+  }
+
+  fn now() -> DateTime<Utc> {
+    DateTime::parse_from_rfc3339("2026-01-01T12:00:00Z")
+      .unwrap()
+      .with_timezone(&Utc)
+  }
+
+  #[test]
+  fn test_refresh_due_when_never_fetched() {
+    let meta = metadata(r#"{"subscription_url": "https://example.com/sub", "content": ""}"#);
+    assert!(meta.is_refresh_due(now()));
+  }
+
+  #[test]
+  fn test_refresh_not_due_within_interval() {
+    let last = (now() - Duration::minutes(30)).to_rfc3339();
+    let meta = metadata(&format!(
+      r#"{{"subscription_url": "https://example.com/sub", "content": "", "last_updated": "{}", "refresh_interval_minutes": 60}}"#,
+      last
+    ));
+    assert!(!meta.is_refresh_due(now()));
+    assert!(meta.is_refresh_due(now() + Duration::minutes(30)));
+  }
+
+  #[test]
+  fn test_refresh_interval_counts_from_last_failure() {
+    // Succeeded long ago but failed recently: wait a full interval before retrying
+    let last_success = (now() - Duration::days(2)).to_rfc3339();
+    let last_failure = (now() - Duration::minutes(10)).to_rfc3339();
+    let meta = metadata(&format!(
+      r#"{{"subscription_url": "https://example.com/sub", "content": "", "last_updated": "{}", "last_failure_at": "{}", "last_error": "HTTP 500", "refresh_interval_minutes": 60}}"#,
+      last_success, last_failure
+    ));
+    assert!(!meta.is_refresh_due(now()));
+  }
+
+  #[test]
+  fn test_refresh_default_interval() {
+    let last = (now() - Duration::hours(6)).to_rfc3339();
+    let meta = metadata(&format!(
+      r#"{{"subscription_url": "https://example.com/sub", "content": "", "last_updated": "{}"}}"#,
+      last
+    ));
+    assert!(!meta.is_refresh_due(now()), "Default interval is 12 hours");
+    assert!(meta.is_refresh_due(now() + Duration::hours(6)));
+  }
+
+  #[test]
+  fn test_refresh_skips_disabled_and_zero_interval() {
+    let disabled = metadata(
+      r#"{"subscription_url": "https://example.com/sub", "content": "", "enabled": false}"#,
+    );
+    assert!(!disabled.is_refresh_due(now()));
+
+    let manual_only = metadata(
+      r#"{"subscription_url": "https://example.com/sub", "content": "", "refresh_interval_minutes": 0}"#,
+    );
+    assert!(!manual_only.is_refresh_due(now()));
+  }
+
+  #[test]
+  fn test_local_sources_are_never_refreshed() {
+    let uploaded = metadata(r#"{"source": "file", "file_name": "nodes.txt", "content": ""}"#);
+    assert_eq!(uploaded.source, SubscriptionSource::File);
+    assert!(!uploaded.is_refresh_due(now()));
+
+    let pasted = metadata(
+      r#"{"subscription_url": "https://example.com/sub", "source": "inline", "content": ""}"#,
+    );
+    assert!(!pasted.is_refresh_due(now()));
+
+    let remote = metadata(r#"{"subscription_url": "https://example.com/sub", "content": ""}"#);
+    assert_eq!(remote.source, SubscriptionSource::Url);
+  }
+
+  #[test]
+  fn test_metadata_typed_defaults() {
+    let meta = metadata(r#"{"subscription_url": "https://example.com/sub"}"#);
+    assert!(meta.enabled);
+    assert_eq!(meta.priority, 0);
+    assert!(meta.labels.is_empty());
+    assert!(meta.notes.is_none());
+    assert!(meta.validate().is_ok());
+  }
+
+  #[test]
+  fn test_metadata_validation() {
+    let valid = metadata(
+      r#"{"subscription_url": "https://example.com/sub", "website_url": "https://example.com", "priority": -5, "labels": ["airport", "backup"], "notes": "renews monthly"}"#,
+    );
+    assert!(valid.validate().is_ok());
+
+    let invalid = [
+      r#"{"subscription_url": ""}"#,
+      r#"{"subscription_url": "ftp://example.com/sub"}"#,
+      r#"{"subscription_url": "https://example.com/sub", "website_url": "example.com"}"#,
+      r#"{"subscription_url": "https://example.com/sub", "labels": [" "]}"#,
+      r#"{"subscription_url": "https://example.com/sub", "labels": ["HK", "hk"]}"#,
+      r#"{"subscription_url": "https://example.com/sub", "labels": ["a-label-that-is-way-too-long-to-be-useful"]}"#,
+    ];
+    for json in invalid {
+      assert!(
+        metadata(json).validate().is_err(),
+        "{} should be rejected",
+        json
+      );
+    }
+
+    let notes = format!(
+      r#"{{"subscription_url": "https://example.com/sub", "notes": "{}"}}"#,
+      "x".repeat(2001)
+    );
+    assert!(metadata(&notes).validate().is_err());
+
+    // Local sources need no URL
+    assert!(
+      metadata(r#"{"source": "inline", "content": ""}"#)
+        .validate()
+        .is_ok()
+    );
+  }
+
+  #[test]
+  fn test_metadata_rejects_wrong_types() {
+    let dto = SubscribeCreateDto {
+      uuid: "u".to_string(),
+      name: "n".to_string(),
+      json: r#"{"subscription_url": "https://example.com/sub", "enabled": "yes"}"#.to_string(),
+    };
+    assert!(dto.metadata().is_err());
+  }
+
+  #[test]
+  fn test_metadata_tag_naming() {
+    let naming = metadata(r#"{"content": ""}"#).tag_naming(&[]);
+    assert_eq!(naming, TagNaming::default());
+
+    let global = vec![RenameRule {
+      pattern: "官网".to_string(),
+      replacement: String::new(),
+    }];
+    let naming = metadata(
+      r#"{"tag_template": "{sub} {name}", "tag_collision": "skip", "rename_rules": [{"pattern": "^\\s+", "replacement": ""}]}"#,
+    )
+    .tag_naming(&global);
+    assert_eq!(naming.template, "{sub} {name}");
+    assert_eq!(naming.collision, TagCollisionStrategy::Skip);
+    assert_eq!(naming.rename_rules.len(), 2);
+    assert_eq!(naming.rename_rules[0], global[0]);
+
+    // A blank template falls back to the default
+    let naming = metadata(r#"{"tag_template": " "}"#).tag_naming(&[]);
+    assert_eq!(naming.template, DEFAULT_TAG_TEMPLATE);
+  }
+
+  #[test]
+  fn test_metadata_rejects_invalid_tag_naming() {
+    // Same checks the create and update handlers run before writing the file
+    let save = |json: &str| {
+      SubscribeCreateDto {
+        uuid: "u".to_string(),
+        name: "n".to_string(),
+        json: json.to_string(),
+      }
+      .metadata()
+      .and_then(|meta| meta.validate())
+    };
+
+    let valid = save(
+      r#"{"subscription_url": "https://example.com/sub", "tag_template": "{country} {name}", "tag_collision": "skip", "rename_rules": [{"pattern": "^\\s+", "replacement": ""}]}"#,
+    );
+    assert!(valid.is_ok(), "{:?}", valid);
+
+    let invalid = [
+      r#"{"subscription_url": "https://example.com/sub", "rename_rules": [{"pattern": "(unclosed"}]}"#,
+      r#"{"subscription_url": "https://example.com/sub", "tag_collision": "rename"}"#,
+      r#"{"subscription_url": "https://example.com/sub", "tag_template": "{name}-{flag}"}"#,
+    ];
+    for json in invalid {
+      assert!(
+        matches!(save(json), Err(AppError::BadRequest(_))),
+        "{} should be rejected",
+        json
+      );
+    }
+  }
+
+  // ========== Subscription-Userinfo tests ==========
+
+  #[test]
+  fn test_parse_subscription_userinfo() {
+    let info = parse_subscription_userinfo(
+      "upload=455727941; download=6174315083; total=1073741824000; expire=1671815872",
+    )
+    .expect("Should parse userinfo header");
+
+    assert_eq!(info.upload, Some(455727941));
+    assert_eq!(info.download, Some(6174315083));
+    assert_eq!(info.total, Some(1073741824000));
+    assert_eq!(info.expire, Some(1671815872));
+  }
+
+  #[test]
+  fn test_parse_subscription_userinfo_variants() {
+    // No spaces, float values, expire=0 (never expires) and unknown keys
+    let info = parse_subscription_userinfo("upload=0;download=1.5e9;total=1e10;expire=0;foo=bar")
+      .expect("Should parse userinfo header");
+    assert_eq!(info.upload, Some(0));
+    assert_eq!(info.download, Some(1_500_000_000));
+    assert_eq!(info.total, Some(10_000_000_000));
+    assert_eq!(info.expire, None);
+
+    assert!(parse_subscription_userinfo("").is_none());
+    assert!(parse_subscription_userinfo("garbage").is_none());
+  }
+
+  #[test]
+  fn test_summarize_usage_statuses() {
+    let gb = 1024 * 1024 * 1024;
+    let info = |used: u64, total: u64, expire_days: Option<i64>| SubscriptionUserinfo {
+      upload: Some(0),
+      download: Some(used * gb),
+      total: Some(total * gb),
+      expire: expire_days.map(|d| (now() + Duration::days(d)).timestamp()),
+    };
+    let status = |info: &SubscriptionUserinfo| {
+      summarize_usage("u".to_string(), "n".to_string(), Some(info), now()).status
+    };
+
+    assert_eq!(status(&info(10, 100, Some(30))), "ok");
+    assert_eq!(status(&info(95, 100, Some(30))), "low");
+    assert_eq!(status(&info(10, 100, Some(3))), "expiring");
+    assert_eq!(status(&info(120, 100, Some(30))), "exhausted");
+    assert_eq!(status(&info(10, 100, Some(-1))), "expired");
+    assert_eq!(status(&info(10, 100, None)), "ok");
+
+    let unknown = summarize_usage("u".to_string(), "n".to_string(), None, now());
+    assert_eq!(unknown.status, "unknown");
+  }
+
+  #[test]
+  fn test_summarize_usage_values() {
+    let info = SubscriptionUserinfo {
+      upload: Some(100),
+      download: Some(300),
+      total: Some(1000),
+      expire: Some((now() + Duration::days(10)).timestamp()),
+    };
+    let usage = summarize_usage("u".to_string(), "n".to_string(), Some(&info), now());
+
+    assert_eq!(usage.used, Some(400));
+    assert_eq!(usage.remaining, Some(600));
+    assert_eq!(usage.used_percent, Some(40.0));
+    assert_eq!(usage.days_until_expire, Some(10));
+  }
+
+  /// Start a local server that echoes request headers back as the subscription body
+  async fn spawn_mock_server() -> String {
+    async fn echo(headers: HeaderMap) -> impl axum::response::IntoResponse {
+      let header = |name: &str| {
+        headers
+          .get(name)
+          .and_then(|v| v.to_str().ok())
+          .unwrap_or_default()
+          .to_string()
+      };
+      let body = format!(
+        "host={}\nua={}\ntoken={}",
+        header("host"),
+        header("user-agent"),
+        header("x-token")
+      );
+      (
+        [("subscription-userinfo", "upload=1; download=2; total=10")],
+        body,
+      )
+    }
+
+    async fn slow() -> &'static str {
+      tokio::time::sleep(std::time::Duration::from_secs(5)).await;
+      "too late"
+    }
+
+    let app = axum::Router::new()
+      .route("/sub", axum::routing::get(echo))
+      .route("/slow", axum::routing::get(slow));
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+    format!("http://{}", addr)
+  }
+
+  #[tokio::test]
+  async fn test_fetch_uses_default_user_agent() {
+    let base = spawn_mock_server().await;
+    let fetched = fetch_subscription(&format!("{}/sub", base), &FetchOptions::default())
+      .await
+      .unwrap();
+
+    assert!(fetched.content.contains("ua=Mozilla/5.0"));
+    assert_eq!(fetched.userinfo.unwrap().total, Some(10));
+  }
+
+  #[tokio::test]
+  async fn test_fetch_custom_user_agent_and_headers() {
+    let base = spawn_mock_server().await;
+    let options = FetchOptions {
+      user_agent: Some("sing-box".to_string()),
+      headers: HashMap::from([("X-Token".to_string(), "secret".to_string())]),
+      ..Default::default()
+    };
+    let fetched = fetch_subscription(&format!("{}/sub", base), &options)
+      .await
+      .unwrap();
+
+    assert!(fetched.content.contains("ua=sing-box\n"));
+    assert!(fetched.content.contains("token=secret"));
+  }
+
+  #[tokio::test]
+  async fn test_fetch_header_overrides_user_agent() {
+    let base = spawn_mock_server().await;
+    let options = FetchOptions {
+      user_agent: Some("sing-box".to_string()),
+      headers: HashMap::from([("User-Agent".to_string(), "clash.meta".to_string())]),
+      ..Default::default()
+    };
+    let fetched = fetch_subscription(&format!("{}/sub", base), &options)
+      .await
+      .unwrap();
+
+    assert!(fetched.content.contains("ua=clash.meta\n"));
+  }
+
+  #[tokio::test]
+  async fn test_fetch_through_http_proxy() {
+    // The mock server plays the proxy: it receives the absolute-form request for the
+    // unresolvable host and answers it directly
+    let base = spawn_mock_server().await;
+    let options = FetchOptions {
+      proxy: Some(base),
+      ..Default::default()
+    };
+    let fetched = fetch_subscription("http://subscription.invalid/sub", &options)
+      .await
+      .unwrap();
+
+    assert!(fetched.content.contains("host=subscription.invalid"));
+  }
+
+  #[tokio::test]
+  async fn test_fetch_timeout() {
+    let base = spawn_mock_server().await;
+    let options = FetchOptions {
+      timeout_seconds: Some(1),
+      ..Default::default()
+    };
+    let result = fetch_subscription(&format!("{}/slow", base), &options).await;
+
+    assert!(result.is_err());
+  }
+
+  #[tokio::test]
+  async fn test_fetch_rejects_invalid_options() {
+    let options = FetchOptions {
+      headers: HashMap::from([("Bad Header".to_string(), "x".to_string())]),
+      ..Default::default()
+    };
+    let error = fetch_subscription("http://127.0.0.1:1/sub", &options)
+      .await
+      .err()
+      .unwrap();
+    assert!(error.contains("Invalid header name"));
+
+    let options = FetchOptions {
+      proxy: Some("not a proxy url".to_string()),
+      ..Default::default()
+    };
+    let error = fetch_subscription("http://127.0.0.1:1/sub", &options)
+      .await
+      .err()
+      .unwrap();
+    assert!(error.contains("Invalid proxy URL"));
+  }
+
+  #[test]
+  fn test_metadata_reads_fetch_options() {
+    let meta = metadata(
+      r#"{"subscription_url": "https://example.com/sub", "content": "",
+          "user_agent": "clash.meta", "headers": {"X-Token": "abc"},
+          "timeout_seconds": 10, "proxy": "socks5://127.0.0.1:1080"}"#,
+    );
+    let options = &meta.fetch_options;
+    assert_eq!(options.user_agent.as_deref(), Some("clash.meta"));
+    assert_eq!(
+      options.headers.get("X-Token").map(String::as_str),
+      Some("abc")
+    );
+    assert_eq!(options.timeout_seconds, Some(10));
+    assert_eq!(options.proxy.as_deref(), Some("socks5://127.0.0.1:1080"));
+  }
+
+  #[test]
+  fn test_update_keeps_server_owned_metadata() {
+    // Stored after a background refresh; the editor still holds the older copy
+    let stored = serde_json::json!({
+      "subscription_url": "https://example.com/sub",
+      "content": "fresh",
+      "last_updated": "2026-01-02T00:00:00Z",
+      "parse_summary": {"ok": 2, "failed": 0, "unsupported": 0},
+      "userinfo": {"upload": 1}
+    });
+    let edited = serde_json::json!({
+      "subscription_url": "https://example.com/sub",
+      "content": "stale",
+      "last_updated": "2026-01-01T00:00:00Z",
+      "last_error": "timeout",
+      "enabled": false,
+      "notes": "paused"
+    });
+
+    let merged = merge_editable_metadata(
+      stored.as_object().unwrap(),
+      edited.as_object().unwrap().clone(),
+    );
+    assert_eq!(merged["content"], "fresh");
+    assert_eq!(merged["last_updated"], "2026-01-02T00:00:00Z");
+    assert_eq!(merged["parse_summary"], stored["parse_summary"]);
+    assert_eq!(merged["userinfo"], stored["userinfo"]);
+    assert!(!merged.contains_key("last_error"));
+    assert_eq!(merged["enabled"], false);
+    assert_eq!(merged["notes"], "paused");
+  }
+
+  #[tokio::test]
+  async fn test_failed_refresh_keeps_previous_content() {
+    // Serve a node once, then fail every later fetch
+    let calls = std::sync::Arc::new(std::sync::atomic::AtomicUsize::new(0));
+    let handler_calls = calls.clone();
+    let app = axum::Router::new().route(
+      "/sub",
+      axum::routing::get(move || {
+        let calls = handler_calls.clone();
+        async move {
+          match calls.fetch_add(1, std::sync::atomic::Ordering::SeqCst) {
+            0 => (
+              axum::http::StatusCode::OK,
+              "trojan://secret@hk.example.com:443#HK-01",
+            ),
+            // A 200 without any node, e.g. a provider error page
+            1 => (axum::http::StatusCode::OK, "<html>token expired</html>"),
+            _ => (axum::http::StatusCode::INTERNAL_SERVER_ERROR, "down"),
+          }
+        }
+      }),
+    );
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+    let dir = tempfile::tempdir().unwrap();
+    let file_path = dir.path().join("sub.json");
+    let dto = SubscribeCreateDto {
+      uuid: "sub".to_string(),
+      name: "Sub".to_string(),
+      json: serde_json::json!({"subscription_url": format!("http://{}/sub", addr)}).to_string(),
+    };
+    std::fs::write(&file_path, serde_json::to_string(&dto).unwrap()).unwrap();
+    let stored = || -> serde_json::Value {
+      let dto: SubscribeCreateDto =
+        serde_json::from_str(&std::fs::read_to_string(&file_path).unwrap()).unwrap();
+      serde_json::from_str(&dto.json).unwrap()
+    };
+
+    let (parsed, _) = refresh_subscription_file(&file_path).await.unwrap();
+    assert_eq!(parsed.outbounds.len(), 1);
+    let before = stored();
+    assert!(before["content"].as_str().unwrap().contains("HK-01"));
+
+    for _ in 0..2 {
+      assert!(refresh_subscription_file(&file_path).await.is_err());
+      let after = stored();
+      assert_eq!(after["content"], before["content"]);
+      assert_eq!(after["last_updated"], before["last_updated"]);
+      assert!(after["last_error"].is_string());
+      assert!(after["last_failure_at"].is_string());
+    }
+    let after = stored();
+    assert_eq!(
+      crate::backend::subscription_parser::parse_subscription(after["content"].as_str().unwrap())
+        .outbounds
+        .len(),
+      1
+    );
+  }
+}
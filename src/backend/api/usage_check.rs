@@ -1,9 +1,11 @@
 use axum::{Json, extract::Query, response::IntoResponse};
 use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
 use std::path::Path;
 use tokio::fs;
 
-use crate::backend::api::config::ConfigCreateDto;
+use crate::backend::api::config::{ConfigCreateDto, RouteRuleDto};
+use crate::backend::api::generation_context::{GenerationContext, OutboundKind};
 use crate::backend::error::AppError;
 
 #[derive(Debug, Deserialize)]
@@ -39,6 +41,22 @@ pub async fn check_resource_usage(
     }));
   }
 
+  // 订阅通过过滤器被引用：禁用的订阅不提供节点，视为未被使用
+  // 节点标签与配置生成时一致（含冲突后缀）
+  let (subscription_outbounds, mut context) = if query.resource_type == "subscribe" {
+    let mut context = GenerationContext::load().await?;
+    let outbounds = context
+      .subscriptions()
+      .await?
+      .iter()
+      .find(|subscription| subscription.uuid == query.uuid)
+      .map(|subscription| subscription.outbounds.clone())
+      .unwrap_or_default();
+    (outbounds, context)
+  } else {
+    (Vec::new(), GenerationContext::default())
+  };
+
   // 遍历所有 config 文件
   let mut entries = fs::read_dir(configs_dir).await?;
 
@@ -143,6 +161,17 @@ pub async fn check_resource_usage(
               })
               .unwrap_or(false)
           }
+          "subscribe" => {
+            !subscription_outbounds.is_empty()
+              && reachable_filters(&context, &config)?.iter().any(|uuid| {
+                // Filters with invalid patterns select nothing
+                context.filter(uuid).is_ok_and(|filter| {
+                  subscription_outbounds
+                    .iter()
+                    .any(|outbound| filter.matches(outbound, &query.uuid))
+                })
+              })
+          }
           _ => false,
         };
 
@@ -161,3 +190,39 @@ pub async fn check_resource_usage(
     used_by_configs,
   }))
 }
+
+/// Filters a config pulls nodes from, directly or through (nested) outbound groups
+fn reachable_filters(
+  context: &GenerationContext,
+  config: &ConfigCreateDto,
+) -> Result<Vec<String>, AppError> {
+  let mut work_queue = vec![
+    config.route.final_outbound.clone(),
+    config.ext_config.download_detour.clone(),
+  ];
+  if let Some(rules) = &config.route.rules {
+    for rule in rules {
+      match rule {
+        RouteRuleDto::Ruleset { outbound, .. } => work_queue.push(outbound.clone()),
+        RouteRuleDto::Rule { outbound, .. } => work_queue.extend(outbound.clone()),
+      }
+    }
+  }
+  work_queue.extend(config.dns.servers.iter().filter_map(|s| s.detour.clone()));
+
+  let mut visited = HashSet::new();
+  let mut filters = Vec::new();
+  while let Some(uuid) = work_queue.pop() {
+    if !visited.insert(uuid.clone()) {
+      continue;
+    }
+
+    match context.outbound_kind(&uuid) {
+      Ok(OutboundKind::Group) => work_queue.extend(context.group(&uuid)?.outbounds),
+      Ok(OutboundKind::Filter) => filters.push(uuid),
+      _ => {}
+    }
+  }
+
+  Ok(filters)
+}
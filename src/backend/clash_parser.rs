@@ -0,0 +1,587 @@
+/// Clash / Mihomo YAML subscription parsing module
+/// Converts entries of a Clash `proxies:` list into the same sing-box outbound objects
+/// that `subscription_parser::parse_subscription_line` produces for URI subscriptions
+use crate::backend::subscription_parser::{parse_mbps, parse_port_hopping};
+use serde_json::{Map, Value};
+
+/// Check whether subscription content looks like a Clash YAML document (has a top-level `proxies:` key)
+pub fn is_clash_yaml(content: &str) -> bool {
+  content
+    .lines()
+    .any(|line| line.trim_end() == "proxies:" || line.starts_with("proxies: "))
+}
+
+/// Parse Clash YAML content into sing-box outbounds.
+/// Returns None if the content is not valid YAML or has no `proxies` list.
+/// Unsupported or malformed proxy entries are skipped.
+pub fn parse_clash_yaml(content: &str) -> Option<Vec<Value>> {
+  let document: Value = serde_norway::from_str(content).ok()?;
+  let proxies = document.get("proxies")?.as_array()?;
+
+  Some(proxies.iter().filter_map(parse_clash_proxy).collect())
+}
+
+/// Convert a single Clash proxy entry to sing-box outbound format
+pub fn parse_clash_proxy(proxy: &Value) -> Option<Value> {
+  let proxy_type = get_str(proxy, "type")?;
+  match proxy_type {
+    "ss" => parse_clash_shadowsocks(proxy),
+    "vmess" => parse_clash_vmess(proxy),
+    "vless" => parse_clash_vless(proxy),
+    "trojan" => parse_clash_trojan(proxy),
+    "hysteria2" => parse_clash_hysteria2(proxy),
+    "tuic" => parse_clash_tuic(proxy),
+    _ => None,
+  }
+}
+
+/// Get a non-empty string field
+fn get_str<'a>(value: &'a Value, key: &str) -> Option<&'a str> {
+  value
+    .get(key)
+    .and_then(|v| v.as_str())
+    .filter(|s| !s.is_empty())
+}
+
+/// Get a port field, accepting both numbers and numeric strings
+fn get_port(value: &Value, key: &str) -> Option<u16> {
+  match value.get(key)? {
+    Value::Number(n) => n.as_u64().and_then(|n| u16::try_from(n).ok()),
+    Value::String(s) => s.trim().parse::<u16>().ok(),
+    _ => None,
+  }
+}
+
+/// Get a boolean field, accepting booleans and "true"/"1" strings
+fn get_bool(value: &Value, key: &str) -> bool {
+  match value.get(key) {
+    Some(Value::Bool(b)) => *b,
+    Some(Value::String(s)) => s == "true" || s == "1",
+    Some(Value::Number(n)) => n.as_u64() == Some(1),
+    _ => false,
+  }
+}
+
+/// Get a bandwidth field ("100", "100 Mbps" or 100) in Mbps
+fn get_mbps(value: &Value, key: &str) -> Option<u64> {
+  match value.get(key)? {
+    Value::Number(n) => n.as_u64().filter(|v| *v > 0),
+    Value::String(s) => parse_mbps(s),
+    _ => None,
+  }
+}
+
+/// Get a string list field (YAML list or comma-separated string)
+fn get_str_list(value: &Value, key: &str) -> Vec<Value> {
+  match value.get(key) {
+    Some(Value::Array(items)) => items
+      .iter()
+      .filter_map(|v| v.as_str())
+      .map(|s| Value::String(s.to_string()))
+      .collect(),
+    Some(Value::String(s)) => s
+      .split(',')
+      .map(|s| s.trim())
+      .filter(|s| !s.is_empty())
+      .map(|s| Value::String(s.to_string()))
+      .collect(),
+    _ => Vec::new(),
+  }
+}
+
+/// Create the outbound map with tag, type, server and server_port filled in
+fn new_outbound(proxy: &Value, outbound_type: &str) -> Option<Map<String, Value>> {
+  let tag = get_str(proxy, "name").unwrap_or("Unnamed");
+  let server = get_str(proxy, "server")?;
+  let port = get_port(proxy, "port")?;
+
+  let mut outbound = Map::new();
+  outbound.insert("tag".to_string(), Value::String(tag.to_string()));
+  outbound.insert("type".to_string(), Value::String(outbound_type.to_string()));
+  outbound.insert("server".to_string(), Value::String(server.to_string()));
+  outbound.insert("server_port".to_string(), Value::Number(port.into()));
+  Some(outbound)
+}
+
+/// Build TLS object from Clash fields (servername/sni, skip-cert-verify, alpn, client-fingerprint)
+fn build_tls(proxy: &Value) -> Map<String, Value> {
+  let mut tls = Map::new();
+  tls.insert("enabled".to_string(), Value::Bool(true));
+
+  if let Some(sni) = get_str(proxy, "servername").or_else(|| get_str(proxy, "sni")) {
+    tls.insert("server_name".to_string(), Value::String(sni.to_string()));
+  }
+  if get_bool(proxy, "skip-cert-verify") {
+    tls.insert("insecure".to_string(), Value::Bool(true));
+  }
+
+  let alpn = get_str_list(proxy, "alpn");
+  if !alpn.is_empty() {
+    tls.insert("alpn".to_string(), Value::Array(alpn));
+  }
+
+  if let Some(fp) = get_str(proxy, "client-fingerprint") {
+    let mut utls = Map::new();
+    utls.insert("enabled".to_string(), Value::Bool(true));
+    utls.insert("fingerprint".to_string(), Value::String(fp.to_string()));
+    tls.insert("utls".to_string(), Value::Object(utls));
+  }
+
+  tls
+}
+
+/// Build transport object from Clash `network` and `*-opts` fields.
+/// Returns None for plain TCP.
+fn build_transport(proxy: &Value) -> Option<Map<String, Value>> {
+  let network = get_str(proxy, "network")?;
+  let mut transport = Map::new();
+
+  match network {
+    "ws" => {
+      transport.insert("type".to_string(), Value::String("ws".to_string()));
+      if let Some(opts) = proxy.get("ws-opts") {
+        if let Some(path) = get_str(opts, "path") {
+          transport.insert("path".to_string(), Value::String(path.to_string()));
+        }
+        if let Some(headers) = opts.get("headers").and_then(|h| h.as_object())
+          && !headers.is_empty()
+        {
+          transport.insert("headers".to_string(), Value::Object(headers.clone()));
+        }
+      }
+    }
+    "h2" => {
+      // sing-box uses "http" for HTTP/2
+      transport.insert("type".to_string(), Value::String("http".to_string()));
+      if let Some(opts) = proxy.get("h2-opts") {
+        if let Some(path) = get_str(opts, "path") {
+          transport.insert("path".to_string(), Value::String(path.to_string()));
+        }
+        let hosts = get_str_list(opts, "host");
+        if !hosts.is_empty() {
+          transport.insert("host".to_string(), Value::Array(hosts));
+        }
+      }
+    }
+    "http" => {
+      transport.insert("type".to_string(), Value::String("http".to_string()));
+      if let Some(opts) = proxy.get("http-opts") {
+        // Clash allows a list of paths; sing-box takes a single path
+        let paths = get_str_list(opts, "path");
+        if let Some(path) = paths.first() {
+          transport.insert("path".to_string(), path.clone());
+        }
+        if let Some(method) = get_str(opts, "method") {
+          transport.insert("method".to_string(), Value::String(method.to_string()));
+        }
+      }
+    }
+    "grpc" => {
+      transport.insert("type".to_string(), Value::String("grpc".to_string()));
+      if let Some(service_name) = proxy
+        .get("grpc-opts")
+        .and_then(|opts| get_str(opts, "grpc-service-name"))
+      {
+        transport.insert(
+          "service_name".to_string(),
+          Value::String(service_name.to_string()),
+        );
+      }
+    }
+    _ => return None,
+  }
+
+  Some(transport)
+}
+
+/// Parse Clash `ss` proxy
+fn parse_clash_shadowsocks(proxy: &Value) -> Option<Value> {
+  let mut outbound = new_outbound(proxy, "shadowsocks")?;
+  let method = get_str(proxy, "cipher")?;
+  let password = get_str(proxy, "password")?;
+  outbound.insert("method".to_string(), Value::String(method.to_string()));
+  outbound.insert("password".to_string(), Value::String(password.to_string()));
+
+  // Map simple-obfs and v2ray-plugin to their sing-box plugin names
+  if let Some(plugin) = get_str(proxy, "plugin") {
+    let opts = proxy.get("plugin-opts");
+    let mode = opts.and_then(|o| get_str(o, "mode")).unwrap_or("");
+    let host = opts.and_then(|o| get_str(o, "host"));
+    let (plugin_name, mut plugin_opts) = match plugin {
+      "obfs" => ("obfs-local", format!("obfs={}", mode)),
+      "v2ray-plugin" => ("v2ray-plugin", format!("mode={}", mode)),
+      _ => return Some(Value::Object(outbound)),
+    };
+    if let Some(host) = host {
+      let key = if plugin == "obfs" {
+        "obfs-host"
+      } else {
+        "host"
+      };
+      plugin_opts.push_str(&format!(";{}={}", key, host));
+    }
+    if plugin == "v2ray-plugin" {
+      if let Some(path) = opts.and_then(|o| get_str(o, "path")) {
+        plugin_opts.push_str(&format!(";path={}", path));
+      }
+      if opts.is_some_and(|o| get_bool(o, "tls")) {
+        plugin_opts.push_str(";tls");
+      }
+    }
+    outbound.insert("plugin".to_string(), Value::String(plugin_name.to_string()));
+    outbound.insert("plugin_opts".to_string(), Value::String(plugin_opts));
+  }
+
+  Some(Value::Object(outbound))
+}
+
+/// Parse Clash `vmess` proxy
+fn parse_clash_vmess(proxy: &Value) -> Option<Value> {
+  let mut outbound = new_outbound(proxy, "vmess")?;
+  let uuid = get_str(proxy, "uuid")?;
+  outbound.insert("uuid".to_string(), Value::String(uuid.to_string()));
+
+  let alter_id = match proxy.get("alterId") {
+    Some(Value::Number(n)) => n.as_u64().unwrap_or(0),
+    Some(Value::String(s)) => s.parse::<u64>().unwrap_or(0),
+    _ => 0,
+  };
+  outbound.insert("alter_id".to_string(), Value::Number(alter_id.into()));
+
+  if let Some(cipher) = get_str(proxy, "cipher") {
+    outbound.insert("security".to_string(), Value::String(cipher.to_string()));
+  }
+
+  if get_bool(proxy, "tls") {
+    outbound.insert("tls".to_string(), Value::Object(build_tls(proxy)));
+  }
+
+  if let Some(transport) = build_transport(proxy) {
+    outbound.insert("transport".to_string(), Value::Object(transport));
+  }
+
+  Some(Value::Object(outbound))
+}
+
+/// Parse Clash `vless` proxy
+fn parse_clash_vless(proxy: &Value) -> Option<Value> {
+  let mut outbound = new_outbound(proxy, "vless")?;
+  let uuid = get_str(proxy, "uuid")?;
+  outbound.insert("uuid".to_string(), Value::String(uuid.to_string()));
+
+  if let Some(flow) = get_str(proxy, "flow") {
+    outbound.insert("flow".to_string(), Value::String(flow.to_string()));
+  }
+
+  let reality_opts = proxy.get("reality-opts").filter(|o| o.is_object());
+  if get_bool(proxy, "tls") || reality_opts.is_some() {
+    let mut tls = build_tls(proxy);
+    if let Some(opts) = reality_opts {
+      let mut reality = Map::new();
+      reality.insert("enabled".to_string(), Value::Bool(true));
+      if let Some(pbk) = get_str(opts, "public-key") {
+        reality.insert("public_key".to_string(), Value::String(pbk.to_string()));
+      }
+      if let Some(sid) = get_str(opts, "short-id") {
+        reality.insert("short_id".to_string(), Value::String(sid.to_string()));
+      }
+      tls.insert("reality".to_string(), Value::Object(reality));
+    }
+    outbound.insert("tls".to_string(), Value::Object(tls));
+  }
+
+  if let Some(transport) = build_transport(proxy) {
+    outbound.insert("transport".to_string(), Value::Object(transport));
+  }
+
+  Some(Value::Object(outbound))
+}
+
+/// Parse Clash `trojan` proxy
+fn parse_clash_trojan(proxy: &Value) -> Option<Value> {
+  let mut outbound = new_outbound(proxy, "trojan")?;
+  let password = get_str(proxy, "password")?;
+  outbound.insert("password".to_string(), Value::String(password.to_string()));
+
+  // Trojan always uses TLS; like the URI parser, default to insecure=true
+  // for compatibility with self-signed certificates
+  let mut tls = build_tls(proxy);
+  tls.insert("insecure".to_string(), Value::Bool(true));
+  outbound.insert("tls".to_string(), Value::Object(tls));
+
+  if let Some(transport) = build_transport(proxy) {
+    outbound.insert("transport".to_string(), Value::Object(transport));
+  }
+
+  Some(Value::Object(outbound))
+}
+
+/// Parse Clash `hysteria2` proxy
+fn parse_clash_hysteria2(proxy: &Value) -> Option<Value> {
+  let mut outbound = new_outbound(proxy, "hysteria2")?;
+
+  if let Some(password) = get_str(proxy, "password").or_else(|| get_str(proxy, "auth")) {
+    outbound.insert("password".to_string(), Value::String(password.to_string()));
+  }
+
+  // Port hopping: "20000-30000,40000" -> ["20000:30000", "40000"]
+  if let Some(ports) = get_str(proxy, "ports").and_then(parse_port_hopping) {
+    outbound.insert("server_ports".to_string(), ports);
+  }
+
+  if let Some(up) = get_mbps(proxy, "up") {
+    outbound.insert("up_mbps".to_string(), Value::Number(up.into()));
+  }
+  if let Some(down) = get_mbps(proxy, "down") {
+    outbound.insert("down_mbps".to_string(), Value::Number(down.into()));
+  }
+
+  if let Some(obfs) = get_str(proxy, "obfs").filter(|o| *o != "none") {
+    let mut obfs_obj = Map::new();
+    obfs_obj.insert("type".to_string(), Value::String(obfs.to_string()));
+    if let Some(obfs_password) = get_str(proxy, "obfs-password") {
+      obfs_obj.insert(
+        "password".to_string(),
+        Value::String(obfs_password.to_string()),
+      );
+    }
+    outbound.insert("obfs".to_string(), Value::Object(obfs_obj));
+  }
+
+  outbound.insert("tls".to_string(), Value::Object(build_tls(proxy)));
+
+  Some(Value::Object(outbound))
+}
+
+/// Parse Clash `tuic` proxy (v5, uuid + password)
+fn parse_clash_tuic(proxy: &Value) -> Option<Value> {
+  let mut outbound = new_outbound(proxy, "tuic")?;
+  let uuid = get_str(proxy, "uuid")?;
+  outbound.insert("uuid".to_string(), Value::String(uuid.to_string()));
+
+  if let Some(password) = get_str(proxy, "password") {
+    outbound.insert("password".to_string(), Value::String(password.to_string()));
+  }
+  if let Some(cc) = get_str(proxy, "congestion-controller") {
+    outbound.insert(
+      "congestion_control".to_string(),
+      Value::String(cc.to_string()),
+    );
+  }
+  if let Some(mode) = get_str(proxy, "udp-relay-mode") {
+    outbound.insert(
+      "udp_relay_mode".to_string(),
+      Value::String(mode.to_string()),
+    );
+  }
+  if get_bool(proxy, "reduce-rtt") {
+    outbound.insert("zero_rtt_handshake".to_string(), Value::Bool(true));
+  }
+
+  let mut tls = build_tls(proxy);
+  if get_bool(proxy, "disable-sni") {
+    tls.insert("disable_sni".to_string(), Value::Bool(true));
+  }
+  outbound.insert("tls".to_string(), Value::Object(tls));
+
+  Some(Value::Object(outbound))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  const CLASH_YAML: &str = r#"
+port: 7890
+mode: rule
+proxies:
+  - name: "SS-Node"
+    type: ss
+    server: ss.example.com
+    port: 8388
+    cipher: aes-256-gcm
+    password: "ss-pass"
+    plugin: obfs
+    plugin-opts:
+      mode: http
+      host: bing.com
+  - name: "VMess-WS"
+    type: vmess
+    server: vmess.example.com
+    port: 443
+    uuid: vmess-uuid
+    alterId: 0
+    cipher: auto
+    tls: true
+    servername: vmess.example.com
+    network: ws
+    ws-opts:
+      path: /ray
+      headers:
+        Host: cdn.example.com
+  - name: "VLESS-Reality"
+    type: vless
+    server: vless.example.com
+    port: "443"
+    uuid: vless-uuid
+    flow: xtls-rprx-vision
+    tls: true
+    servername: www.apple.com
+    client-fingerprint: chrome
+    reality-opts:
+      public-key: pbk-value
+      short-id: 6c4ef2d5
+  - name: "Trojan-gRPC"
+    type: trojan
+    server: trojan.example.com
+    port: 443
+    password: trojan-pass
+    sni: trojan.example.com
+    network: grpc
+    grpc-opts:
+      grpc-service-name: TrojanService
+  - name: "HY2"
+    type: hysteria2
+    server: hy2.example.com
+    port: 8443
+    password: hy2-pass
+    obfs: salamander
+    obfs-password: obfs-pass
+    up: "30 Mbps"
+    down: 100
+    sni: hy2.example.com
+    skip-cert-verify: true
+  - name: "TUIC"
+    type: tuic
+    server: tuic.example.com
+    port: 10443
+    uuid: tuic-uuid
+    password: tuic-pass
+    congestion-controller: bbr
+    udp-relay-mode: native
+    alpn: [h3]
+  - name: "Snell-Unsupported"
+    type: snell
+    server: snell.example.com
+    port: 443
+proxy-groups:
+  - name: Proxy
+    type: select
+    proxies: [SS-Node]
+"#;
+
+  #[test]
+  fn test_is_clash_yaml() {
+    assert!(is_clash_yaml(CLASH_YAML));
+    assert!(!is_clash_yaml("trojan://pass@example.com:443#Node"));
+    assert!(!is_clash_yaml("dHJvamFuOi8vcGFzc0BleGFtcGxlLmNvbTo0NDM="));
+  }
+
+  #[test]
+  fn test_parse_clash_yaml_skips_unsupported() {
+    let outbounds = parse_clash_yaml(CLASH_YAML).expect("Should parse Clash YAML");
+    assert_eq!(outbounds.len(), 6, "snell entry should be skipped");
+
+    let types: Vec<&str> = outbounds
+      .iter()
+      .filter_map(|ob| ob["type"].as_str())
+      .collect();
+    assert_eq!(
+      types,
+      vec![
+        "shadowsocks",
+        "vmess",
+        "vless",
+        "trojan",
+        "hysteria2",
+        "tuic"
+      ]
+    );
+  }
+
+  #[test]
+  fn test_parse_clash_shadowsocks_with_obfs() {
+    let outbounds = parse_clash_yaml(CLASH_YAML).unwrap();
+    let ss = &outbounds[0];
+
+    assert_eq!(ss["tag"], "SS-Node");
+    assert_eq!(ss["server"], "ss.example.com");
+    assert_eq!(ss["server_port"], 8388);
+    assert_eq!(ss["method"], "aes-256-gcm");
+    assert_eq!(ss["password"], "ss-pass");
+    assert_eq!(ss["plugin"], "obfs-local");
+    assert_eq!(ss["plugin_opts"], "obfs=http;obfs-host=bing.com");
+  }
+
+  #[test]
+  fn test_parse_clash_vmess_ws_tls() {
+    let outbounds = parse_clash_yaml(CLASH_YAML).unwrap();
+    let vmess = &outbounds[1];
+
+    assert_eq!(vmess["uuid"], "vmess-uuid");
+    assert_eq!(vmess["alter_id"], 0);
+    assert_eq!(vmess["security"], "auto");
+    assert_eq!(vmess["tls"]["enabled"], true);
+    assert_eq!(vmess["tls"]["server_name"], "vmess.example.com");
+    assert_eq!(vmess["transport"]["type"], "ws");
+    assert_eq!(vmess["transport"]["path"], "/ray");
+    assert_eq!(vmess["transport"]["headers"]["Host"], "cdn.example.com");
+  }
+
+  #[test]
+  fn test_parse_clash_vless_reality() {
+    let outbounds = parse_clash_yaml(CLASH_YAML).unwrap();
+    let vless = &outbounds[2];
+
+    // Port given as a string should still be parsed
+    assert_eq!(vless["server_port"], 443);
+    assert_eq!(vless["flow"], "xtls-rprx-vision");
+
+    let tls = &vless["tls"];
+    assert_eq!(tls["server_name"], "www.apple.com");
+    assert_eq!(tls["reality"]["enabled"], true);
+    assert_eq!(tls["reality"]["public_key"], "pbk-value");
+    assert_eq!(tls["reality"]["short_id"], "6c4ef2d5");
+    assert_eq!(tls["utls"]["fingerprint"], "chrome");
+    assert!(vless.get("transport").is_none());
+  }
+
+  #[test]
+  fn test_parse_clash_trojan_grpc() {
+    let outbounds = parse_clash_yaml(CLASH_YAML).unwrap();
+    let trojan = &outbounds[3];
+
+    assert_eq!(trojan["password"], "trojan-pass");
+    assert_eq!(trojan["tls"]["enabled"], true);
+    assert_eq!(trojan["tls"]["insecure"], true);
+    assert_eq!(trojan["tls"]["server_name"], "trojan.example.com");
+    assert_eq!(trojan["transport"]["type"], "grpc");
+    assert_eq!(trojan["transport"]["service_name"], "TrojanService");
+  }
+
+  #[test]
+  fn test_parse_clash_hysteria2_and_tuic() {
+    let outbounds = parse_clash_yaml(CLASH_YAML).unwrap();
+
+    let hy2 = &outbounds[4];
+    assert_eq!(hy2["password"], "hy2-pass");
+    assert_eq!(hy2["up_mbps"], 30);
+    assert_eq!(hy2["down_mbps"], 100);
+    assert_eq!(hy2["obfs"]["type"], "salamander");
+    assert_eq!(hy2["obfs"]["password"], "obfs-pass");
+    assert_eq!(hy2["tls"]["insecure"], true);
+
+    let tuic = &outbounds[5];
+    assert_eq!(tuic["uuid"], "tuic-uuid");
+    assert_eq!(tuic["password"], "tuic-pass");
+    assert_eq!(tuic["congestion_control"], "bbr");
+    assert_eq!(tuic["udp_relay_mode"], "native");
+    assert_eq!(tuic["tls"]["alpn"][0], "h3");
+  }
+
+  #[test]
+  fn test_parse_clash_yaml_without_proxies() {
+    assert!(parse_clash_yaml("port: 7890\nmode: rule\n").is_none());
+    assert!(parse_clash_yaml("proxies: [unclosed").is_none());
+  }
+}
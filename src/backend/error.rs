@@ -8,6 +8,7 @@ use axum::{
 pub enum AppError {
   BadRequest(String),
   NotFound(String),
+  BadGateway(String),
   InternalServerError(String),
   AnyhowError(anyhow::Error),
 }
@@ -18,6 +19,7 @@ impl IntoResponse for AppError {
     match self {
       AppError::BadRequest(msg) => (StatusCode::BAD_REQUEST, msg).into_response(),
       AppError::NotFound(msg) => (StatusCode::NOT_FOUND, msg).into_response(),
+      AppError::BadGateway(msg) => (StatusCode::BAD_GATEWAY, msg).into_response(),
       AppError::InternalServerError(msg) => {
         (StatusCode::INTERNAL_SERVER_ERROR, msg).into_response()
       }
@@ -1,4 +1,9 @@
 pub mod api;
+pub mod clash_parser;
 pub mod error;
 pub mod migration;
+pub mod node_filter;
+pub mod pseudo_nodes;
+pub mod scheduler;
 pub mod subscription_parser;
+pub mod tag_template;
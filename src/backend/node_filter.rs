@@ -0,0 +1,396 @@
+/// Node conditions for filters
+/// Matches subscription outbounds on protocol, server, port, transport, TLS and source subscription
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::cmp::Ordering;
+use std::net::IpAddr;
+
+use crate::backend::error::AppError;
+
+/// How the conditions of a filter (and its tag pattern) are combined
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConditionMode {
+  #[default]
+  And,
+  Or,
+}
+
+/// Extra node conditions of a filter
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FilterConditions {
+  #[serde(default)]
+  pub mode: ConditionMode,
+  #[serde(default)]
+  pub rules: Vec<NodeCondition>,
+}
+
+/// One condition on a subscription outbound; list values match if any entry matches
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "field", rename_all = "snake_case")]
+pub enum NodeCondition {
+  /// Outbound `type`, e.g. "vless", "hysteria2"
+  Type {
+    values: Vec<String>,
+  },
+  /// Domain (also matches subdomains), IP address or CIDR
+  Server {
+    values: Vec<String>,
+  },
+  /// Single ports ("443") or inclusive ranges ("8000-9000")
+  Port {
+    values: Vec<String>,
+  },
+  /// `transport.type` ("ws", "grpc", ...); nodes without a transport count as "tcp"
+  Transport {
+    values: Vec<String>,
+  },
+  Tls {
+    enabled: bool,
+  },
+  Reality {
+    enabled: bool,
+  },
+  /// Source subscription uuids
+  Subscription {
+    values: Vec<String>,
+  },
+}
+
+impl NodeCondition {
+  /// Check the condition against an outbound coming from `subscription_uuid`
+  pub fn matches(&self, outbound: &Value, subscription_uuid: &str) -> bool {
+    let text = |key: &str| {
+      outbound
+        .get(key)
+        .and_then(|v| v.as_str())
+        .unwrap_or_default()
+    };
+    let tls = outbound.get("tls");
+    let flag = |value: Option<&Value>| {
+      value
+        .and_then(|v| v.get("enabled"))
+        .and_then(|e| e.as_bool())
+        .unwrap_or(false)
+    };
+
+    match self {
+      NodeCondition::Type { values } => values.iter().any(|v| v.eq_ignore_ascii_case(text("type"))),
+      NodeCondition::Server { values } => values.iter().any(|v| server_matches(v, text("server"))),
+      NodeCondition::Port { values } => outbound
+        .get("server_port")
+        .and_then(|p| p.as_u64())
+        .is_some_and(|port| {
+          values
+            .iter()
+            .filter_map(|v| parse_port_range(v))
+            .any(|(start, end)| (start..=end).contains(&port))
+        }),
+      NodeCondition::Transport { values } => {
+        let transport = outbound
+          .get("transport")
+          .and_then(|t| t.get("type"))
+          .and_then(|t| t.as_str())
+          .unwrap_or("tcp");
+        values.iter().any(|v| v.eq_ignore_ascii_case(transport))
+      }
+      NodeCondition::Tls { enabled } => flag(tls) == *enabled,
+      NodeCondition::Reality { enabled } => flag(tls.and_then(|t| t.get("reality"))) == *enabled,
+      NodeCondition::Subscription { values } => values.iter().any(|v| v == subscription_uuid),
+    }
+  }
+
+  /// Reject conditions that could never match as intended
+  pub fn validate(&self) -> Result<(), AppError> {
+    let non_empty = |field: &str, values: &[String]| {
+      if values.iter().all(|v| v.trim().is_empty()) {
+        Err(AppError::BadRequest(format!(
+          "Filter condition \"{}\" needs at least one value",
+          field
+        )))
+      } else {
+        Ok(())
+      }
+    };
+
+    match self {
+      NodeCondition::Type { values } => non_empty("type", values),
+      NodeCondition::Transport { values } => non_empty("transport", values),
+      NodeCondition::Subscription { values } => non_empty("subscription", values),
+      NodeCondition::Server { values } => {
+        non_empty("server", values)?;
+        if let Some(bad) = values
+          .iter()
+          .find(|v| v.contains('/') && parse_cidr(v).is_none())
+        {
+          return Err(AppError::BadRequest(format!("Invalid CIDR: {}", bad)));
+        }
+        Ok(())
+      }
+      NodeCondition::Port { values } => {
+        non_empty("port", values)?;
+        if let Some(bad) = values.iter().find(|v| parse_port_range(v).is_none()) {
+          return Err(AppError::BadRequest(format!("Invalid port range: {}", bad)));
+        }
+        Ok(())
+      }
+      NodeCondition::Tls { .. } | NodeCondition::Reality { .. } => Ok(()),
+    }
+  }
+}
+
+impl FilterConditions {
+  /// Combine the tag pattern result with the node conditions.
+  /// `tag_match` is None when the filter has no tag pattern.
+  pub fn combine(
+    &self,
+    tag_match: Option<bool>,
+    outbound: &Value,
+    subscription_uuid: &str,
+  ) -> bool {
+    let mut results = tag_match.into_iter().chain(
+      self
+        .rules
+        .iter()
+        .map(|rule| rule.matches(outbound, subscription_uuid)),
+    );
+
+    match self.mode {
+      ConditionMode::And => results.all(|matched| matched),
+      // An OR filter without any condition matches nothing
+      ConditionMode::Or => results.any(|matched| matched),
+    }
+  }
+
+  pub fn validate(&self) -> Result<(), AppError> {
+    self.rules.iter().try_for_each(NodeCondition::validate)
+  }
+}
+
+/// Parse "443" or "8000-9000" into an inclusive range
+fn parse_port_range(value: &str) -> Option<(u64, u64)> {
+  let value = value.trim();
+  let (start, end) = match value.split_once('-') {
+    Some((start, end)) => (
+      start.trim().parse::<u16>().ok()?,
+      end.trim().parse::<u16>().ok()?,
+    ),
+    None => {
+      let port = value.parse::<u16>().ok()?;
+      (port, port)
+    }
+  };
+  (start <= end).then_some((u64::from(start), u64::from(end)))
+}
+
+/// Parse "10.0.0.0/8" or "2001:db8::/32" into a network address and prefix length
+fn parse_cidr(value: &str) -> Option<(IpAddr, u8)> {
+  let (addr, prefix) = value.trim().split_once('/')?;
+  let addr: IpAddr = addr.parse().ok()?;
+  let prefix: u8 = prefix.parse().ok()?;
+  let max = if addr.is_ipv4() { 32 } else { 128 };
+  (prefix <= max).then_some((addr, prefix))
+}
+
+fn in_cidr(ip: IpAddr, network: IpAddr, prefix: u8) -> bool {
+  match (ip, network) {
+    (IpAddr::V4(ip), IpAddr::V4(network)) => {
+      let mask = u32::MAX.checked_shl(32 - u32::from(prefix)).unwrap_or(0);
+      u32::from(ip) & mask == u32::from(network) & mask
+    }
+    (IpAddr::V6(ip), IpAddr::V6(network)) => {
+      let mask = u128::MAX.checked_shl(128 - u32::from(prefix)).unwrap_or(0);
+      u128::from(ip) & mask == u128::from(network) & mask
+    }
+    _ => false,
+  }
+}
+
+/// Match a server against a domain (suffix), IP or CIDR value
+fn server_matches(value: &str, server: &str) -> bool {
+  let value = value.trim();
+  let server = server.trim_start_matches('[').trim_end_matches(']');
+  if value.is_empty() || server.is_empty() {
+    return false;
+  }
+
+  if value.contains('/') {
+    return match (parse_cidr(value), server.parse::<IpAddr>()) {
+      (Some((network, prefix)), Ok(ip)) => in_cidr(ip, network, prefix),
+      _ => false,
+    };
+  }
+
+  let value = value.trim_start_matches('.').to_ascii_lowercase();
+  let server = server.to_ascii_lowercase();
+  server == value || server.ends_with(&format!(".{}", value))
+}
+
+/// Compare tags with digit runs as numbers, so "HK 2" sorts before "HK 10"
+pub fn natural_cmp(a: &str, b: &str) -> Ordering {
+  let mut a_chars = a.chars().peekable();
+  let mut b_chars = b.chars().peekable();
+
+  loop {
+    match (a_chars.peek(), b_chars.peek()) {
+      (None, None) => return a.cmp(b),
+      (None, Some(_)) => return Ordering::Less,
+      (Some(_), None) => return Ordering::Greater,
+      (Some(x), Some(y)) if x.is_ascii_digit() && y.is_ascii_digit() => {
+        let take_digits = |chars: &mut std::iter::Peekable<std::str::Chars>| {
+          let mut digits = String::new();
+          while let Some(c) = chars.next_if(char::is_ascii_digit) {
+            digits.push(c);
+          }
+          digits
+        };
+        let x = take_digits(&mut a_chars);
+        let y = take_digits(&mut b_chars);
+        let (x_trimmed, y_trimmed) = (x.trim_start_matches('0'), y.trim_start_matches('0'));
+        let ordering = x_trimmed
+          .len()
+          .cmp(&y_trimmed.len())
+          .then_with(|| x_trimmed.cmp(y_trimmed));
+        if ordering != Ordering::Equal {
+          return ordering;
+        }
+      }
+      (Some(x), Some(y)) => {
+        let ordering = x.cmp(y);
+        if ordering != Ordering::Equal {
+          return ordering;
+        }
+        a_chars.next();
+        b_chars.next();
+      }
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use serde_json::json;
+
+  fn reality_node() -> Value {
+    json!({
+      "type": "vless",
+      "tag": "HK-01",
+      "server": "hk1.example.com",
+      "server_port": 443,
+      "tls": {"enabled": true, "reality": {"enabled": true, "public_key": "k"}}
+    })
+  }
+
+  fn ws_node() -> Value {
+    json!({
+      "type": "vmess",
+      "tag": "JP-01",
+      "server": "10.1.2.3",
+      "server_port": 8080,
+      "transport": {"type": "ws", "path": "/"}
+    })
+  }
+
+  fn condition(json: Value) -> NodeCondition {
+    serde_json::from_value(json).unwrap()
+  }
+
+  #[test]
+  fn test_type_transport_and_tls() {
+    let vless = condition(json!({"field": "type", "values": ["VLESS"]}));
+    assert!(vless.matches(&reality_node(), "a"));
+    assert!(!vless.matches(&ws_node(), "a"));
+
+    let tcp = condition(json!({"field": "transport", "values": ["tcp"]}));
+    assert!(tcp.matches(&reality_node(), "a"));
+    let ws = condition(json!({"field": "transport", "values": ["ws", "grpc"]}));
+    assert!(ws.matches(&ws_node(), "a"));
+
+    let reality = condition(json!({"field": "reality", "enabled": true}));
+    assert!(reality.matches(&reality_node(), "a"));
+    assert!(!reality.matches(&ws_node(), "a"));
+
+    let no_tls = condition(json!({"field": "tls", "enabled": false}));
+    assert!(no_tls.matches(&ws_node(), "a"));
+    assert!(!no_tls.matches(&reality_node(), "a"));
+  }
+
+  #[test]
+  fn test_server_domain_and_cidr() {
+    assert!(server_matches("example.com", "hk1.example.com"));
+    assert!(server_matches("hk1.example.com", "HK1.example.com"));
+    assert!(!server_matches("example.com", "badexample.com"));
+    assert!(server_matches("10.0.0.0/8", "10.1.2.3"));
+    assert!(!server_matches("10.0.0.0/8", "11.1.2.3"));
+    assert!(server_matches("2001:db8::/32", "[2001:db8::1]"));
+    assert!(server_matches("0.0.0.0/0", "1.2.3.4"));
+    assert!(!server_matches("10.0.0.0/8", "example.com"));
+  }
+
+  #[test]
+  fn test_port_ranges() {
+    let ports = condition(json!({"field": "port", "values": ["443", "8000-9000"]}));
+    assert!(ports.matches(&reality_node(), "a"));
+    assert!(ports.matches(&ws_node(), "a"));
+
+    assert_eq!(parse_port_range("8000-9000"), Some((8000, 9000)));
+    assert_eq!(parse_port_range("9000-8000"), None);
+    assert_eq!(parse_port_range("http"), None);
+  }
+
+  #[test]
+  fn test_combine_and_or() {
+    let conditions: FilterConditions = serde_json::from_value(json!({
+      "rules": [
+        {"field": "reality", "enabled": true},
+        {"field": "subscription", "values": ["provider-a"]},
+        {"field": "port", "values": ["443"]}
+      ]
+    }))
+    .unwrap();
+    assert!(conditions.combine(None, &reality_node(), "provider-a"));
+    assert!(!conditions.combine(None, &reality_node(), "provider-b"));
+    assert!(!conditions.combine(Some(false), &reality_node(), "provider-a"));
+
+    let any: FilterConditions = serde_json::from_value(json!({
+      "mode": "or",
+      "rules": [{"field": "type", "values": ["hysteria2"]}]
+    }))
+    .unwrap();
+    assert!(any.combine(Some(true), &ws_node(), "a"));
+    assert!(!any.combine(Some(false), &ws_node(), "a"));
+    assert!(!any.combine(None, &ws_node(), "a"));
+  }
+
+  #[test]
+  fn test_validate() {
+    assert!(
+      condition(json!({"field": "port", "values": ["1-2"]}))
+        .validate()
+        .is_ok()
+    );
+    assert!(
+      condition(json!({"field": "port", "values": ["70000"]}))
+        .validate()
+        .is_err()
+    );
+    assert!(
+      condition(json!({"field": "server", "values": ["10.0.0.0/33"]}))
+        .validate()
+        .is_err()
+    );
+    assert!(
+      condition(json!({"field": "type", "values": []}))
+        .validate()
+        .is_err()
+    );
+  }
+
+  #[test]
+  fn test_natural_cmp() {
+    let mut tags = vec!["HK 10", "HK 2", "HK 1", "JP 01", "HK", "HK 02"];
+    tags.sort_by(|a, b| natural_cmp(a, b));
+    assert_eq!(tags, vec!["HK", "HK 1", "HK 02", "HK 2", "HK 10", "JP 01"]);
+  }
+}
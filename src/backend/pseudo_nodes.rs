@@ -0,0 +1,258 @@
+/// Informational pseudo-node detection
+/// Providers often append fake entries (remaining traffic, expiry date, ...) pointing at
+/// loopback addresses or dummy ports; these are dropped before nodes reach filters and groups
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::net::IpAddr;
+
+/// Name keywords that mark a node as informational
+pub const DEFAULT_PSEUDO_NODE_KEYWORDS: &[&str] = &[
+  "剩余流量",
+  "流量剩余",
+  "已用流量",
+  "到期时间",
+  "过期时间",
+  "套餐到期",
+  "距离下次重置",
+  "下次重置",
+  "重置剩余",
+  "Remaining Traffic",
+  "Traffic Reset",
+  "Expire Date",
+];
+
+/// Serde default for the `enabled` switches (pseudo-node filter, subscriptions)
+pub(crate) fn default_enabled() -> bool {
+  true
+}
+
+fn default_excluded_ports() -> Vec<u16> {
+  vec![0, 1]
+}
+
+/// Pseudo-node exclusion settings (`pseudo_node_filter` in the subscription metadata)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PseudoNodeFilter {
+  #[serde(default = "default_enabled")]
+  pub enabled: bool,
+  /// Name keywords; the built-in list is used when unset
+  #[serde(default, skip_serializing_if = "Option::is_none")]
+  pub keywords: Option<Vec<String>>,
+  /// Drop nodes whose server is a loopback, private, link-local or unspecified IP
+  #[serde(default = "default_enabled")]
+  pub exclude_private_addresses: bool,
+  #[serde(default = "default_excluded_ports")]
+  pub excluded_ports: Vec<u16>,
+}
+
+impl Default for PseudoNodeFilter {
+  fn default() -> Self {
+    Self {
+      enabled: true,
+      keywords: None,
+      exclude_private_addresses: true,
+      excluded_ports: default_excluded_ports(),
+    }
+  }
+}
+
+impl PseudoNodeFilter {
+  /// Read the filter from subscription metadata, falling back to the defaults
+  pub fn from_metadata(metadata: &Value) -> Self {
+    metadata
+      .get("pseudo_node_filter")
+      .and_then(|f| serde_json::from_value(f.clone()).ok())
+      .unwrap_or_default()
+  }
+
+  /// Why `outbound` is a pseudo-node, or None if it looks like a real one
+  pub fn match_reason(&self, outbound: &Value) -> Option<String> {
+    if !self.enabled {
+      return None;
+    }
+
+    let tag = outbound
+      .get("tag")
+      .and_then(|t| t.as_str())
+      .unwrap_or_default();
+    let keywords: Vec<&str> = match &self.keywords {
+      Some(keywords) => keywords.iter().map(String::as_str).collect(),
+      None => DEFAULT_PSEUDO_NODE_KEYWORDS.to_vec(),
+    };
+    let tag_lower = tag.to_lowercase();
+    if let Some(keyword) = keywords
+      .iter()
+      .find(|k| !k.is_empty() && tag_lower.contains(&k.to_lowercase()))
+    {
+      return Some(format!("name contains \"{}\"", keyword));
+    }
+
+    let server = outbound
+      .get("server")
+      .and_then(|s| s.as_str())
+      .unwrap_or_default();
+    if self.exclude_private_addresses && is_private_server(server) {
+      return Some(format!("private server address {}", server));
+    }
+
+    if let Some(port) = outbound.get("server_port").and_then(|p| p.as_u64())
+      && self.excluded_ports.iter().any(|p| u64::from(*p) == port)
+    {
+      return Some(format!("excluded port {}", port));
+    }
+
+    None
+  }
+}
+
+/// A node removed as informational, with the reason
+#[derive(Debug, Clone, Serialize)]
+pub struct ExcludedNode {
+  pub tag: String,
+  pub server: String,
+  pub server_port: Option<u64>,
+  pub reason: String,
+}
+
+/// Split outbounds into real nodes and excluded pseudo-nodes
+pub fn exclude_pseudo_nodes(
+  outbounds: Vec<Value>,
+  filter: &PseudoNodeFilter,
+) -> (Vec<Value>, Vec<ExcludedNode>) {
+  let mut kept = Vec::with_capacity(outbounds.len());
+  let mut excluded = Vec::new();
+
+  for outbound in outbounds {
+    match filter.match_reason(&outbound) {
+      Some(reason) => excluded.push(ExcludedNode {
+        tag: outbound
+          .get("tag")
+          .and_then(|t| t.as_str())
+          .unwrap_or_default()
+          .to_string(),
+        server: outbound
+          .get("server")
+          .and_then(|s| s.as_str())
+          .unwrap_or_default()
+          .to_string(),
+        server_port: outbound.get("server_port").and_then(|p| p.as_u64()),
+        reason,
+      }),
+      None => kept.push(outbound),
+    }
+  }
+
+  (kept, excluded)
+}
+
+fn is_private_server(server: &str) -> bool {
+  let host = server.trim_start_matches('[').trim_end_matches(']');
+  if host.eq_ignore_ascii_case("localhost") {
+    return true;
+  }
+
+  match host.parse::<IpAddr>() {
+    Ok(IpAddr::V4(ip)) => {
+      ip.is_loopback() || ip.is_private() || ip.is_link_local() || ip.is_unspecified()
+    }
+    Ok(IpAddr::V6(ip)) => {
+      // fc00::/7 unique local, fe80::/10 link-local
+      let first = ip.segments()[0];
+      ip.is_loopback()
+        || ip.is_unspecified()
+        || (first & 0xfe00) == 0xfc00
+        || (first & 0xffc0) == 0xfe80
+    }
+    Err(_) => false,
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use serde_json::json;
+
+  fn node(tag: &str, server: &str, port: u64) -> Value {
+    json!({"type": "trojan", "tag": tag, "server": server, "server_port": port})
+  }
+
+  #[test]
+  fn test_default_filter_drops_pseudo_nodes() {
+    let outbounds = vec![
+      node("剩余流量：100GB", "hk.example.com", 443),
+      node("到期时间 2026-12-01", "hk.example.com", 443),
+      node("Info", "127.0.0.1", 443),
+      node("Info v6", "::1", 443),
+      node("Dummy", "hk.example.com", 1),
+      node("HK 01", "hk.example.com", 443),
+      node("Public IP", "8.8.8.8", 443),
+    ];
+    let (kept, excluded) = exclude_pseudo_nodes(outbounds, &PseudoNodeFilter::default());
+
+    let kept_tags: Vec<&str> = kept.iter().map(|o| o["tag"].as_str().unwrap()).collect();
+    assert_eq!(kept_tags, vec!["HK 01", "Public IP"]);
+    assert_eq!(excluded.len(), 5);
+    assert!(excluded[0].reason.contains("剩余流量"));
+    assert!(excluded[2].reason.contains("private"));
+    assert!(excluded[4].reason.contains("port 1"));
+  }
+
+  #[test]
+  fn test_private_addresses() {
+    for server in [
+      "10.0.0.1",
+      "192.168.1.1",
+      "172.16.0.1",
+      "0.0.0.0",
+      "localhost",
+      "fd00::1",
+    ] {
+      assert!(is_private_server(server), "{} should be private", server);
+    }
+    for server in ["1.1.1.1", "example.com", "2001:db8::1"] {
+      assert!(!is_private_server(server), "{} should be public", server);
+    }
+  }
+
+  #[test]
+  fn test_filter_from_metadata() {
+    let filter = PseudoNodeFilter::from_metadata(&json!({"content": ""}));
+    assert_eq!(filter, PseudoNodeFilter::default());
+
+    let filter = PseudoNodeFilter::from_metadata(&json!({
+      "pseudo_node_filter": {
+        "keywords": ["公告"],
+        "exclude_private_addresses": false,
+        "excluded_ports": []
+      }
+    }));
+    assert!(filter.enabled);
+    assert!(
+      filter
+        .match_reason(&node("公告: 新年快乐", "a.example.com", 443))
+        .is_some()
+    );
+    // Custom keywords replace the built-in list
+    assert!(
+      filter
+        .match_reason(&node("剩余流量 1GB", "a.example.com", 443))
+        .is_none()
+    );
+    assert!(
+      filter
+        .match_reason(&node("LAN", "192.168.1.1", 0))
+        .is_none()
+    );
+  }
+
+  #[test]
+  fn test_disabled_filter_keeps_everything() {
+    let filter = PseudoNodeFilter {
+      enabled: false,
+      ..Default::default()
+    };
+    let (kept, excluded) = exclude_pseudo_nodes(vec![node("剩余流量", "127.0.0.1", 0)], &filter);
+    assert_eq!(kept.len(), 1);
+    assert!(excluded.is_empty());
+  }
+}
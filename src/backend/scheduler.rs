@@ -0,0 +1,25 @@
+/// Background scheduler
+/// Periodically refreshes subscriptions whose auto-refresh interval has elapsed
+use std::time::Duration;
+use tokio::time::MissedTickBehavior;
+
+use crate::backend::api::subscribe;
+
+/// How often the scheduler checks for subscriptions that are due
+const CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Spawn the subscription refresh loop. Subscriptions are refreshed one at a time,
+/// and a sweep never overlaps with the next one.
+pub fn spawn_subscription_refresh() {
+  tokio::spawn(async {
+    let mut ticker = tokio::time::interval(CHECK_INTERVAL);
+    ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+    loop {
+      ticker.tick().await;
+      if let Err(e) = subscribe::refresh_due_subscriptions().await {
+        log::error!("Scheduled subscription refresh failed: {:?}", e);
+      }
+    }
+  });
+}
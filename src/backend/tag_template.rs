@@ -0,0 +1,450 @@
+/// Tag naming for subscription nodes
+/// Cleans node names with regex rename rules, renders each tag from a per-subscription
+/// template and resolves collisions; shared by the config generator and the previews
+use crate::backend::error::AppError;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashSet;
+
+/// Template used when a subscription doesn't set `tag_template`
+pub const DEFAULT_TAG_TEMPLATE: &str = "{name}-{sub}";
+
+/// What to do when a rendered tag is already taken
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TagCollisionStrategy {
+  /// Append `-2`, `-3`, ... until the tag is unique
+  #[default]
+  Suffix,
+  /// Drop the later node
+  Skip,
+  /// Fail the whole operation
+  Error,
+}
+
+/// A regex find/replace applied to the original node name. `replacement` may use `$1`, `${name}`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RenameRule {
+  pub pattern: String,
+  #[serde(default)]
+  pub replacement: String,
+}
+
+/// Compile rename rules, rejecting invalid patterns
+pub fn compile_rename_rules(rules: &[RenameRule]) -> Result<Vec<(Regex, String)>, AppError> {
+  rules
+    .iter()
+    .map(|rule| {
+      Regex::new(&rule.pattern)
+        .map(|re| (re, rule.replacement.clone()))
+        .map_err(|e| {
+          AppError::BadRequest(format!("Invalid rename pattern {}: {}", rule.pattern, e))
+        })
+    })
+    .collect()
+}
+
+/// Run compiled rename rules in order on a node name
+pub fn apply_rename_rules(rules: &[(Regex, String)], name: &str) -> String {
+  let renamed = rules
+    .iter()
+    .fold(name.to_string(), |name, (re, replacement)| {
+      re.replace_all(&name, replacement.as_str()).into_owned()
+    });
+  renamed.trim().to_string()
+}
+
+/// Per-subscription tag naming options (`tag_template` / `tag_collision` / `rename_rules`
+/// in the metadata)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TagNaming {
+  pub template: String,
+  pub collision: TagCollisionStrategy,
+  /// Global rules followed by the subscription's own rules
+  pub rename_rules: Vec<RenameRule>,
+}
+
+impl Default for TagNaming {
+  fn default() -> Self {
+    Self {
+      template: DEFAULT_TAG_TEMPLATE.to_string(),
+      collision: TagCollisionStrategy::default(),
+      rename_rules: Vec::new(),
+    }
+  }
+}
+
+/// Placeholders understood by `render_tag`
+const TAG_PLACEHOLDERS: [&str; 5] = ["name", "sub", "index", "protocol", "country"];
+
+/// Reject templates with unknown or unclosed placeholders
+pub fn check_tag_template(template: &str) -> Result<(), AppError> {
+  let mut rest = template;
+  while let Some(start) = rest.find('{') {
+    let Some(len) = rest[start..].find('}') else {
+      return Err(AppError::BadRequest(format!(
+        "Unclosed placeholder in tag template: {}",
+        template
+      )));
+    };
+    let placeholder = &rest[start + 1..start + len];
+    if !TAG_PLACEHOLDERS.contains(&placeholder) {
+      return Err(AppError::BadRequest(format!(
+        "Unknown placeholder {{{}}} in tag template: {}",
+        placeholder, template
+      )));
+    }
+    rest = &rest[start + len + 1..];
+  }
+  Ok(())
+}
+
+/// Render a tag template. Supported placeholders: `{name}` (node name after rename rules),
+/// `{sub}` (subscription name), `{index}` (1-based position in the subscription),
+/// `{protocol}` (outbound type) and `{country}` (ISO code guessed from the name, may be empty).
+pub fn render_tag(
+  template: &str,
+  outbound: &Value,
+  subscription_name: &str,
+  index: usize,
+) -> String {
+  let name = outbound
+    .get("tag")
+    .and_then(|t| t.as_str())
+    .unwrap_or_default();
+  let protocol = outbound
+    .get("type")
+    .and_then(|t| t.as_str())
+    .unwrap_or_default();
+
+  // Single pass, so placeholders appearing inside node names are left alone
+  let mut rendered = String::new();
+  let mut rest = template;
+  while let Some(start) = rest.find('{') {
+    let Some(len) = rest[start..].find('}') else {
+      break;
+    };
+    rendered.push_str(&rest[..start]);
+    let placeholder = &rest[start..=start + len];
+    match &placeholder[1..len] {
+      "name" => rendered.push_str(name),
+      "sub" => rendered.push_str(subscription_name),
+      "index" => rendered.push_str(&index.to_string()),
+      "protocol" => rendered.push_str(protocol),
+      "country" => rendered.push_str(&detect_country(name).unwrap_or_default()),
+      _ => rendered.push_str(placeholder),
+    }
+    rest = &rest[start + len + 1..];
+  }
+  rendered.push_str(rest);
+  let rendered = rendered.trim();
+
+  // A template made only of empty placeholders must not produce an empty tag
+  if rendered.is_empty() {
+    name.to_string()
+  } else {
+    rendered.to_string()
+  }
+}
+
+/// Work out the final tag of each outbound, or None for nodes dropped by the `skip` strategy.
+/// `used_tags` holds the tags already taken (by earlier subscriptions in the same config)
+/// and is updated with the new ones.
+pub fn assign_tags(
+  outbounds: &[Value],
+  subscription_name: &str,
+  naming: &TagNaming,
+  used_tags: &mut HashSet<String>,
+) -> Result<Vec<Option<String>>, AppError> {
+  let rules = compile_rename_rules(&naming.rename_rules)?;
+  let mut tags = Vec::with_capacity(outbounds.len());
+
+  for (index, outbound) in outbounds.iter().enumerate() {
+    let original = outbound
+      .get("tag")
+      .and_then(|t| t.as_str())
+      .unwrap_or_default();
+    let mut renamed = outbound.clone();
+    if let Some(obj) = renamed.as_object_mut() {
+      obj.insert(
+        "tag".to_string(),
+        Value::String(apply_rename_rules(&rules, original)),
+      );
+    }
+    let rendered = render_tag(&naming.template, &renamed, subscription_name, index + 1);
+
+    let tag = if !used_tags.contains(&rendered) {
+      rendered
+    } else {
+      match naming.collision {
+        TagCollisionStrategy::Suffix => (2..)
+          .map(|n| format!("{}-{}", rendered, n))
+          .find(|candidate| !used_tags.contains(candidate))
+          .unwrap_or(rendered),
+        TagCollisionStrategy::Skip => {
+          tags.push(None);
+          continue;
+        }
+        TagCollisionStrategy::Error => {
+          return Err(AppError::BadRequest(format!(
+            "Duplicate tag \"{}\" in subscription {}",
+            rendered, subscription_name
+          )));
+        }
+      }
+    };
+
+    used_tags.insert(tag.clone());
+    tags.push(Some(tag));
+  }
+
+  Ok(tags)
+}
+
+/// Rename the tags of one subscription's outbounds (see `assign_tags`)
+pub fn apply_tag_naming(
+  outbounds: Vec<Value>,
+  subscription_name: &str,
+  naming: &TagNaming,
+  used_tags: &mut HashSet<String>,
+) -> Result<Vec<Value>, AppError> {
+  let tags = assign_tags(&outbounds, subscription_name, naming, used_tags)?;
+
+  Ok(
+    outbounds
+      .into_iter()
+      .zip(tags)
+      .filter_map(|(mut outbound, tag)| {
+        let tag = tag?;
+        if let Some(obj) = outbound.as_object_mut() {
+          obj.insert("tag".to_string(), Value::String(tag));
+        }
+        Some(outbound)
+      })
+      .collect(),
+  )
+}
+
+/// Country keywords matched against node names, checked in order
+const COUNTRY_KEYWORDS: &[(&str, &[&str])] = &[
+  ("HK", &["香港", "Hong Kong", "HongKong"]),
+  ("TW", &["台湾", "臺灣", "Taiwan"]),
+  ("JP", &["日本", "Japan", "Tokyo", "Osaka"]),
+  ("SG", &["新加坡", "狮城", "Singapore"]),
+  ("KR", &["韩国", "韓國", "Korea", "Seoul"]),
+  (
+    "US",
+    &[
+      "美国",
+      "United States",
+      "America",
+      "Los Angeles",
+      "San Jose",
+    ],
+  ),
+  ("GB", &["英国", "United Kingdom", "London"]),
+  ("DE", &["德国", "Germany", "Frankfurt"]),
+  ("FR", &["法国", "France", "Paris"]),
+  ("NL", &["荷兰", "Netherlands", "Amsterdam"]),
+  ("RU", &["俄罗斯", "Russia", "Moscow"]),
+  ("CA", &["加拿大", "Canada"]),
+  ("AU", &["澳大利亚", "澳洲", "Australia", "Sydney"]),
+  ("IN", &["印度", "India"]),
+  ("TR", &["土耳其", "Turkey", "Türkiye"]),
+];
+
+/// ISO codes recognised as standalone words in node names (e.g. `HK 01`, `JP-Tokyo`)
+const COUNTRY_CODES: &[&str] = &[
+  "HK", "TW", "JP", "SG", "KR", "US", "UK", "GB", "DE", "FR", "NL", "RU", "CA", "AU", "IN", "TR",
+];
+
+/// Guess the country of a node from its name: a flag emoji first, then names, then ISO codes
+pub fn detect_country(name: &str) -> Option<String> {
+  if let Some(code) = flag_emoji_code(name) {
+    return Some(code);
+  }
+
+  let lower = name.to_lowercase();
+  if let Some((code, _)) = COUNTRY_KEYWORDS.iter().find(|(_, keywords)| {
+    keywords
+      .iter()
+      .any(|keyword| lower.contains(&keyword.to_lowercase()))
+  }) {
+    return Some(code.to_string());
+  }
+
+  name
+    .split(|c: char| !c.is_ascii_alphabetic())
+    .find(|word| COUNTRY_CODES.contains(word))
+    .map(|code| if code == "UK" { "GB" } else { code }.to_string())
+}
+
+/// Decode the first flag emoji (a pair of regional indicator symbols) into its ISO code
+fn flag_emoji_code(name: &str) -> Option<String> {
+  const REGIONAL_A: u32 = 0x1F1E6;
+  let letter = |c: char| {
+    let offset = (c as u32).checked_sub(REGIONAL_A)?;
+    (offset < 26).then(|| char::from(b'A' + offset as u8))
+  };
+
+  let chars: Vec<char> = name.chars().collect();
+  chars
+    .windows(2)
+    .find_map(|pair| Some(format!("{}{}", letter(pair[0])?, letter(pair[1])?)))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use serde_json::json;
+
+  fn node(tag: &str, node_type: &str) -> Value {
+    json!({"type": node_type, "tag": tag, "server": "example.com", "server_port": 443})
+  }
+
+  #[test]
+  fn test_render_default_template() {
+    let tag = render_tag(
+      DEFAULT_TAG_TEMPLATE,
+      &node("HK 01", "trojan"),
+      "Provider",
+      1,
+    );
+    assert_eq!(tag, "HK 01-Provider");
+  }
+
+  #[test]
+  fn test_render_all_placeholders() {
+    let tag = render_tag(
+      "[{sub}] {country} {protocol} #{index} {name}",
+      &node("🇯🇵 Tokyo 02", "vless"),
+      "Provider",
+      7,
+    );
+    assert_eq!(tag, "[Provider] JP vless #7 🇯🇵 Tokyo 02");
+  }
+
+  #[test]
+  fn test_render_leaves_unknown_and_nested_placeholders() {
+    let tag = render_tag(
+      "{name} {unknown} {",
+      &node("a {sub} b", "trojan"),
+      "Provider",
+      1,
+    );
+    assert_eq!(tag, "a {sub} b {unknown} {");
+  }
+
+  #[test]
+  fn test_render_empty_result_falls_back_to_name() {
+    let tag = render_tag("{country}", &node("Mystery node", "trojan"), "Provider", 1);
+    assert_eq!(tag, "Mystery node");
+  }
+
+  #[test]
+  fn test_detect_country() {
+    let country = detect_country;
+    assert_eq!(country("🇭🇰 Premium").as_deref(), Some("HK"));
+    assert_eq!(country("🇧🇷 São Paulo").as_deref(), Some("BR"));
+    assert_eq!(country("香港 IPLC 01").as_deref(), Some("HK"));
+    assert_eq!(country("Singapore-Direct").as_deref(), Some("SG"));
+    assert_eq!(country("US-LAX 02").as_deref(), Some("US"));
+    assert_eq!(country("UK 01").as_deref(), Some("GB"));
+    // Codes only match as whole words
+    assert_eq!(detect_country("Premium Bus"), None);
+    assert_eq!(detect_country("Unnamed"), None);
+  }
+
+  #[test]
+  fn test_apply_tag_naming_suffix() {
+    let naming = TagNaming {
+      template: "{country}".to_string(),
+      collision: TagCollisionStrategy::Suffix,
+      ..Default::default()
+    };
+    let mut used = HashSet::from(["JP".to_string()]);
+    let outbounds = vec![
+      node("HK 01", "trojan"),
+      node("HK 02", "trojan"),
+      node("JP 01", "trojan"),
+    ];
+    let renamed = apply_tag_naming(outbounds, "Provider", &naming, &mut used).unwrap();
+
+    let tags: Vec<&str> = renamed.iter().map(|o| o["tag"].as_str().unwrap()).collect();
+    assert_eq!(tags, vec!["HK", "HK-2", "JP-2"]);
+    assert!(used.contains("HK-2"));
+  }
+
+  #[test]
+  fn test_apply_tag_naming_skip_and_error() {
+    let outbounds = vec![node("HK 01", "trojan"), node("HK 02", "trojan")];
+
+    let skip = TagNaming {
+      template: "{country}".to_string(),
+      collision: TagCollisionStrategy::Skip,
+      ..Default::default()
+    };
+    let renamed =
+      apply_tag_naming(outbounds.clone(), "Provider", &skip, &mut HashSet::new()).unwrap();
+    assert_eq!(renamed.len(), 1);
+
+    let error = TagNaming {
+      template: "{country}".to_string(),
+      collision: TagCollisionStrategy::Error,
+      ..Default::default()
+    };
+    let result = apply_tag_naming(outbounds, "Provider", &error, &mut HashSet::new());
+    assert!(matches!(result, Err(AppError::BadRequest(_))));
+  }
+
+  #[test]
+  fn test_check_tag_template() {
+    assert!(check_tag_template("{country} {name} ({sub}) #{index} {protocol}").is_ok());
+    assert!(check_tag_template("plain").is_ok());
+    assert!(check_tag_template("{name}-{flag}").is_err());
+    assert!(check_tag_template("{name}-{sub").is_err());
+  }
+
+  fn rule(pattern: &str, replacement: &str) -> RenameRule {
+    RenameRule {
+      pattern: pattern.to_string(),
+      replacement: replacement.to_string(),
+    }
+  }
+
+  #[test]
+  fn test_apply_rename_rules_in_order() {
+    let rules = compile_rename_rules(&[
+      rule(r"[\x{1F1E6}-\x{1F1FF}]{2}", ""),
+      rule(r"(?i)hong\s*kong|香港", "HK"),
+      rule(r"\s*\|.*$", ""),
+      rule(r"(\w+) (\d+)", "$1-$2"),
+    ])
+    .unwrap();
+
+    assert_eq!(
+      apply_rename_rules(&rules, "🇭🇰 Hong Kong 01 | 官网 xx.com"),
+      "HK-01"
+    );
+    assert_eq!(apply_rename_rules(&rules, "香港 02"), "HK-02");
+  }
+
+  #[test]
+  fn test_compile_rename_rules_rejects_invalid_pattern() {
+    let result = compile_rename_rules(&[rule("(unclosed", "")]);
+    assert!(matches!(result, Err(AppError::BadRequest(_))));
+  }
+
+  #[test]
+  fn test_rename_rules_run_before_template() {
+    let naming = TagNaming {
+      rename_rules: vec![rule("剩余流量.*", "")],
+      ..Default::default()
+    };
+    let outbounds = vec![node("JP 01 剩余流量 10G", "trojan")];
+    let tags = assign_tags(&outbounds, "Provider", &naming, &mut HashSet::new()).unwrap();
+
+    assert_eq!(tags, vec![Some("JP 01-Provider".to_string())]);
+  }
+}
@@ -29,8 +29,15 @@ export interface RouteConfig {
   default_domain_resolver?: string;
 }
 
+export interface DedupConfig {
+  enabled: boolean;
+  /** Subscription UUIDs whose copy of a duplicated node wins, highest priority first */
+  prefer_subscriptions?: string[];
+}
+
 export interface ExtConfig {
   download_detour: string;
+  dedup?: DedupConfig;
 }
 
 export interface ConfigCreateDto {
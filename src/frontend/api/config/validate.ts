@@ -0,0 +1,28 @@
+import { http } from "@/api/http";
+
+export type ValidationIssueKind =
+  | "dangling_uuid"
+  | "ambiguous_uuid"
+  | "generation"
+  | "empty_group"
+  | "empty_tag"
+  | "duplicate_tag"
+  | "unselected_inbound"
+  | "unknown_rule_set"
+  | "unknown_outbound";
+
+export interface ValidationIssue {
+  kind: ValidationIssueKind;
+  message: string;
+}
+
+export interface ValidationReport {
+  valid: boolean;
+  errors: ValidationIssue[];
+  warnings: ValidationIssue[];
+}
+
+/** Run the config's generation on the server and report its problems */
+export async function validateConfig(uuid: string) {
+  return await http.get(`config/${uuid}/validate`).json<ValidationReport>();
+}
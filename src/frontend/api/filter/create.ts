@@ -2,19 +2,58 @@ import { http } from "@/api/http";
 import { nameSchema } from "@/lib/validation";
 import { z } from "zod";
 
+export type NodeCondition =
+  | { field: "type"; values: string[] }
+  /** Domain (also matches subdomains), IP address or CIDR */
+  | { field: "server"; values: string[] }
+  /** Single ports ("443") or ranges ("8000-9000") */
+  | { field: "port"; values: string[] }
+  /** Nodes without a transport count as "tcp" */
+  | { field: "transport"; values: string[] }
+  | { field: "tls"; enabled: boolean }
+  | { field: "reality"; enabled: boolean }
+  /** Source subscription uuids */
+  | { field: "subscription"; values: string[] };
+
+export interface FilterConditions {
+  /** How the rules and a non-empty pattern combine, defaults to "and" */
+  mode?: "and" | "or";
+  rules: NodeCondition[];
+}
+
+/** Parse the conditions editor text; empty text means no conditions */
+export function parseFilterConditions(
+  text: string,
+): FilterConditions | undefined {
+  if (!text.trim()) return undefined;
+  const parsed = JSON.parse(text);
+  if (!parsed || !Array.isArray(parsed.rules)) {
+    throw new Error("Conditions must be an object with a rules array");
+  }
+  return parsed as FilterConditions;
+}
+
+/** Order of matched nodes; "natural" compares numbers in tags numerically */
+export type FilterSort = "subscription" | "name" | "natural";
+
 export const filterCreateSchema = z.object({
   uuid: z.string(),
   name: nameSchema,
   filter_type: z.enum(["simple", "regex"], {
     message: "Filter type must be 'simple' or 'regex'",
   }),
-  pattern: z.string().min(1, {
-    message: "Pattern cannot be empty.",
-  }),
+  pattern: z.string(),
   except: z.string().optional(),
+  case_insensitive: z.boolean().optional(),
+  whole_word: z.boolean().optional(),
+  sort: z.enum(["subscription", "name", "natural"]).optional(),
+  limit: z.number().int().min(1).optional(),
+  subscriptions: z.array(z.string()).optional(),
 });
 
-export type FilterCreateDto = z.infer<typeof filterCreateSchema>;
+export type FilterCreateDto = z.infer<typeof filterCreateSchema> & {
+  conditions?: FilterConditions;
+};
 
 export function createFilter(filterData: FilterCreateDto) {
   return http.post("filter", {
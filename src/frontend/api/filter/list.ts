@@ -1,3 +1,4 @@
+import type { FilterConditions, FilterSort } from "@/api/filter/create";
 import { http } from "@/api/http";
 import { useQuery } from "@tanstack/react-query";
 
@@ -7,6 +8,14 @@ export interface FilterListDto {
   filter_type: "simple" | "regex";
   pattern: string;
   except?: string;
+  case_insensitive?: boolean;
+  whole_word?: boolean;
+  sort?: FilterSort;
+  /** Keep only the first N nodes after sorting */
+  limit?: number;
+  /** Subscription uuids to draw nodes from; all subscriptions when empty */
+  subscriptions?: string[];
+  conditions?: FilterConditions;
 }
 
 async function listFilters() {
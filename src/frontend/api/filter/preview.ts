@@ -0,0 +1,45 @@
+import type { DedupConfig } from "@/api/config/create";
+import type { FilterConditions, FilterSort } from "@/api/filter/create";
+import { http } from "@/api/http";
+import { useMutation } from "@tanstack/react-query";
+
+export interface FilterPreviewDto {
+  filter_type: "simple" | "regex";
+  pattern: string;
+  except?: string;
+  case_insensitive?: boolean;
+  whole_word?: boolean;
+  sort?: FilterSort;
+  /** Keep only the first N nodes after sorting */
+  limit?: number;
+  /** Subscription uuids to draw nodes from; all subscriptions when empty */
+  subscriptions?: string[];
+  conditions?: FilterConditions;
+  /** Deduplication to apply first, as in the config being built */
+  dedup?: DedupConfig;
+}
+
+export interface SubscriptionFilterPreview {
+  uuid: string;
+  name: string;
+  matched: string[];
+  /** Matched the pattern but removed by except */
+  excluded: string[];
+  unmatched: string[];
+}
+
+export interface FilterPreviewResultDto {
+  /** Tags the filter would emit, in generation order */
+  matched: string[];
+  subscriptions: SubscriptionFilterPreview[];
+}
+
+export const useFilterPreview = () => {
+  return useMutation({
+    mutationFn: async (data: FilterPreviewDto) => {
+      return await http
+        .post("filter/preview", { json: data })
+        .json<FilterPreviewResultDto>();
+    },
+  });
+};
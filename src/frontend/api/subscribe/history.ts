@@ -0,0 +1,64 @@
+import { http } from "@/api/http";
+import { useQuery } from "@tanstack/react-query";
+import type { SubscriptionFormat } from "./refresh";
+
+export interface SnapshotSummaryDto {
+  id: string;
+  fetched_at: string;
+  format: SubscriptionFormat;
+  node_count: number;
+}
+
+export interface NodeSummary {
+  tag: string;
+  type: string;
+  server: string;
+  server_port: number | null;
+}
+
+export interface ChangedNode {
+  before: NodeSummary;
+  after: NodeSummary;
+  fields: string[];
+}
+
+export interface SubscriptionDiffDto {
+  from: string;
+  to: string;
+  added: NodeSummary[];
+  removed: NodeSummary[];
+  changed: ChangedNode[];
+  unchanged: number;
+}
+
+export const useSubscribeHistory = (uuid: string | null) => {
+  return useQuery({
+    queryKey: ["subscribe", "history", uuid],
+    queryFn: async () => {
+      if (!uuid) return [];
+      return await http
+        .get("subscribe/history", { searchParams: { uuid } })
+        .json<SnapshotSummaryDto[]>();
+    },
+    enabled: !!uuid,
+  });
+};
+
+export const useSubscribeDiff = (
+  uuid: string | null,
+  from?: string,
+  to?: string,
+) => {
+  return useQuery({
+    queryKey: ["subscribe", "diff", uuid, from, to],
+    queryFn: async () => {
+      const searchParams: Record<string, string> = { uuid: uuid ?? "" };
+      if (from) searchParams.from = from;
+      if (to) searchParams.to = to;
+      return await http
+        .get("subscribe/diff", { searchParams })
+        .json<SubscriptionDiffDto>();
+    },
+    enabled: !!uuid,
+  });
+};
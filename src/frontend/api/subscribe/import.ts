@@ -0,0 +1,28 @@
+import { http } from "@/api/http";
+import type { SubscribeRefreshResultDto } from "./refresh";
+
+/** Upload a subscription file; creates the subscription when `uuid` is new */
+export function uploadSubscribe(uuid: string, name: string, file: File) {
+  const formData = new FormData();
+  formData.append("uuid", uuid);
+  formData.append("name", name);
+  formData.append("file", file);
+
+  return http
+    .post("subscribe/upload", { body: formData })
+    .json<SubscribeRefreshResultDto>();
+}
+
+export interface SubscribePasteDto {
+  uuid: string;
+  /** Required when creating a new subscription */
+  name?: string;
+  content: string;
+}
+
+/** Save pasted share links or subscription text as the subscription content */
+export function pasteSubscribe(data: SubscribePasteDto) {
+  return http
+    .post("subscribe/paste", { json: data })
+    .json<SubscribeRefreshResultDto>();
+}
@@ -1,10 +1,18 @@
 import { http } from "@/api/http";
 import { useQuery } from "@tanstack/react-query";
 
+export interface SubscriptionUserinfo {
+  upload?: number;
+  download?: number;
+  total?: number;
+  expire?: number;
+}
+
 export interface SubscribeListDto {
   uuid: string;
   name: string;
   json: string;
+  userinfo: SubscriptionUserinfo | null;
 }
 
 export const useSubscribeList = () => {
@@ -1,5 +1,6 @@
 import { http } from "@/api/http";
 import { useQuery } from "@tanstack/react-query";
+import type { ParseSummary, SubscriptionFormat } from "./refresh";
 
 export interface OutboundDto {
   tag: string;
@@ -9,14 +10,40 @@ export interface OutboundDto {
   [key: string]: unknown;
 }
 
+export interface ParseDiagnostic {
+  line_index: number;
+  protocol: string;
+  kind: "failed" | "unsupported";
+  message: string;
+  redacted_line: string;
+}
+
+/** Informational pseudo-node (remaining traffic, expiry, ...) dropped from the subscription */
+export interface ExcludedNode {
+  tag: string;
+  server: string;
+  server_port: number | null;
+  reason: string;
+}
+
+export interface SubscribeOutboundsResultDto {
+  /** Disabled subscriptions list no outbounds */
+  enabled: boolean;
+  format: SubscriptionFormat;
+  outbounds: OutboundDto[];
+  diagnostics: ParseDiagnostic[];
+  summary: ParseSummary;
+  excluded: ExcludedNode[];
+}
+
 export const useSubscribeOutbounds = (uuid: string | null) => {
   return useQuery({
     queryKey: ["subscribe", "outbounds", uuid],
     queryFn: async () => {
-      if (!uuid) return [];
+      if (!uuid) return null;
       return await http
         .get("subscribe/outbounds", { searchParams: { uuid } })
-        .json<OutboundDto[]>();
+        .json<SubscribeOutboundsResultDto>();
     },
     enabled: !!uuid,
   });
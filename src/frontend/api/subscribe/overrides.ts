@@ -0,0 +1,39 @@
+import { http } from "@/api/http";
+import { useMutation, useQuery, useQueryClient } from "@tanstack/react-query";
+
+/** JSON merge patch applied to the subscription nodes a rule matches */
+export interface NodeOverrideRule {
+  name: string;
+  /** Subscription uuids; empty matches every subscription */
+  subscriptions: string[];
+  /** Filter uuid selecting nodes by tag; all nodes when unset */
+  filter?: string;
+  /** Merge patch, e.g. {"tls": {"utls": {"fingerprint": "chrome"}}}; may not touch tag/type */
+  patch: Record<string, unknown>;
+}
+
+export interface NodeOverridesDto {
+  rules: NodeOverrideRule[];
+}
+
+export const useNodeOverrides = () => {
+  return useQuery({
+    queryKey: ["subscribe", "overrides"],
+    queryFn: async () => {
+      return await http.get("subscribe/overrides").json<NodeOverridesDto>();
+    },
+  });
+};
+
+export const useUpdateNodeOverrides = () => {
+  const queryClient = useQueryClient();
+  return useMutation({
+    mutationFn: async (data: NodeOverridesDto) => {
+      await http.put("subscribe/overrides", { json: data });
+    },
+    onSuccess: () => {
+      queryClient.invalidateQueries({ queryKey: ["subscribe", "overrides"] });
+      queryClient.invalidateQueries({ queryKey: ["subscribe", "outbounds"] });
+    },
+  });
+};
@@ -5,14 +5,38 @@ export interface SubscribeRefreshDto {
   uuid: string;
 }
 
+export type SubscriptionFormat =
+  | "base64"
+  | "url_safe_base64"
+  | "plain_uri_list"
+  | "mixed"
+  | "clash_yaml"
+  | "singbox_json"
+  | "unknown";
+
+export interface ParseSummary {
+  ok: number;
+  failed: number;
+  unsupported: number;
+}
+
+export interface SubscribeRefreshResultDto {
+  message: string;
+  format: SubscriptionFormat;
+  node_count: number;
+  parse_summary: ParseSummary;
+}
+
 export const useSubscribeRefresh = () => {
   return useMutation({
     mutationFn: async (subscribeData: SubscribeRefreshDto) => {
-      return await http.post("subscribe/refresh", {
-        searchParams: {
-          uuid: subscribeData.uuid,
-        },
-      });
+      return await http
+        .post("subscribe/refresh", {
+          searchParams: {
+            uuid: subscribeData.uuid,
+          },
+        })
+        .json<SubscribeRefreshResultDto>();
     },
   });
 };
@@ -0,0 +1,53 @@
+import { http } from "@/api/http";
+import { useMutation, useQuery, useQueryClient } from "@tanstack/react-query";
+import type { RenameRule } from "@/types/subscribe";
+
+export interface RenameRulesDto {
+  rules: RenameRule[];
+}
+
+export interface RenamePreviewDto {
+  uuid: string;
+  /** Draft subscription rules; the saved ones are used when omitted */
+  rules?: RenameRule[];
+}
+
+export interface RenamePreviewItemDto {
+  before: string;
+  renamed: string;
+  /** Final tag, null if the node is skipped as a duplicate */
+  after: string | null;
+}
+
+export const useGlobalRenameRules = () => {
+  return useQuery({
+    queryKey: ["subscribe", "rename-rules"],
+    queryFn: async () => {
+      return await http.get("subscribe/rename-rules").json<RenameRulesDto>();
+    },
+  });
+};
+
+export const useUpdateGlobalRenameRules = () => {
+  const queryClient = useQueryClient();
+  return useMutation({
+    mutationFn: async (data: RenameRulesDto) => {
+      await http.put("subscribe/rename-rules", { json: data });
+    },
+    onSuccess: () => {
+      queryClient.invalidateQueries({
+        queryKey: ["subscribe", "rename-rules"],
+      });
+    },
+  });
+};
+
+export const useRenamePreview = () => {
+  return useMutation({
+    mutationFn: async (data: RenamePreviewDto) => {
+      return await http
+        .post("subscribe/rename-preview", { json: data })
+        .json<RenamePreviewItemDto[]>();
+    },
+  });
+};
@@ -0,0 +1,33 @@
+import { http } from "@/api/http";
+import { useQuery } from "@tanstack/react-query";
+
+export type SubscriptionUsageStatus =
+  | "unknown"
+  | "ok"
+  | "low"
+  | "expiring"
+  | "exhausted"
+  | "expired";
+
+export interface SubscriptionUsageDto {
+  uuid: string;
+  name: string;
+  upload: number | null;
+  download: number | null;
+  total: number | null;
+  used: number | null;
+  remaining: number | null;
+  used_percent: number | null;
+  expire_at: string | null;
+  days_until_expire: number | null;
+  status: SubscriptionUsageStatus;
+}
+
+export const useSubscribeUsage = () => {
+  return useQuery({
+    queryKey: ["subscribe", "usage"],
+    queryFn: async () => {
+      return await http.get("subscribe/usage").json<SubscriptionUsageDto[]>();
+    },
+  });
+};
@@ -184,6 +184,9 @@ export function ConfigForm({
 	const [downloadDetour, setDownloadDetour] = useState<string>(
 		initialData?.ext_config?.download_detour || "",
 	);
+	const [dedupEnabled, setDedupEnabled] = useState<boolean>(
+		initialData?.ext_config?.dedup?.enabled ?? false,
+	);
 
 	// 当 initialData 变化时，更新所有状态
 	useEffect(() => {
@@ -201,6 +204,7 @@ export function ConfigForm({
 		setRouteDefaultDomainResolver(initialData?.route?.default_domain_resolver);
 		setExperimental(initialData?.experimental || "");
 		setDownloadDetour(initialData?.ext_config?.download_detour || "");
+		setDedupEnabled(initialData?.ext_config?.dedup?.enabled ?? false);
 	}, [initialData]);
 
 	// 当选中的 DNS server 变化时，清除不在列表中的 default domain resolver
@@ -264,7 +268,12 @@ export function ConfigForm({
 			},
 			experimental,
 			ext_config: {
+				...initialData?.ext_config,
 				download_detour: downloadDetour,
+				dedup: {
+					...initialData?.ext_config?.dedup,
+					enabled: dedupEnabled,
+				},
 			},
 		});
 	};
@@ -419,6 +428,8 @@ export function ConfigForm({
 											<OtherConfigSection
 												downloadDetour={downloadDetour}
 												onDownloadDetourChange={setDownloadDetour}
+												dedupEnabled={dedupEnabled}
+												onDedupEnabledChange={setDedupEnabled}
 											/>
 										</Accordion>
 									</div>
@@ -9,17 +9,22 @@ import {
 	AccordionTrigger,
 } from "@/components/ui/accordion";
 import { Label } from "@/components/ui/label";
+import { Switch } from "@/components/ui/switch";
 import { IconAlertCircle, IconCheck } from "@tabler/icons-react";
 import { useMemo } from "react";
 
 interface OtherConfigSectionProps {
 	downloadDetour: string;
 	onDownloadDetourChange: (value: string) => void;
+	dedupEnabled: boolean;
+	onDedupEnabledChange: (value: boolean) => void;
 }
 
 export function OtherConfigSection({
 	downloadDetour,
 	onDownloadDetourChange,
+	dedupEnabled,
+	onDedupEnabledChange,
 }: OtherConfigSectionProps) {
 	const { data: outboundOptions, isLoading: outboundsLoading } =
 		useOutboundGroupOptions();
@@ -117,6 +122,24 @@ export function OtherConfigSection({
 							</p>
 						)}
 					</div>
+
+					{/* Node Deduplication */}
+					<div className="flex items-start justify-between gap-4">
+						<div>
+							<Label htmlFor="dedup-enabled" className="text-base">
+								Deduplicate Nodes
+							</Label>
+							<p className="text-sm text-muted-foreground mt-1">
+								Keep a single copy of nodes sharing protocol, server, port and
+								credentials across subscriptions, before filters run.
+							</p>
+						</div>
+						<Switch
+							id="dedup-enabled"
+							checked={dedupEnabled}
+							onCheckedChange={onDedupEnabledChange}
+						/>
+					</div>
 				</div>
 			</AccordionContent>
 		</AccordionItem>
@@ -1,4 +1,10 @@
+import { type FilterSort, parseFilterConditions } from "@/api/filter/create";
+import { useFilterPreview } from "@/api/filter/preview";
+import { useSubscribeList } from "@/api/subscribe/list";
 import { FormEditor } from "@/components/form-editor";
+import { Button } from "@/components/ui/button";
+import { Checkbox } from "@/components/ui/checkbox";
+import { Input } from "@/components/ui/input";
 import { Label } from "@/components/ui/label";
 import {
   Select,
@@ -7,8 +13,18 @@ import {
   SelectTrigger,
   SelectValue,
 } from "@/components/ui/select";
+import { Switch } from "@/components/ui/switch";
 import { Textarea } from "@/components/ui/textarea";
 
+const CONDITIONS_PLACEHOLDER = `{
+  "mode": "and",
+  "rules": [
+    { "field": "reality", "enabled": true },
+    { "field": "port", "values": ["443"] },
+    { "field": "subscription", "values": ["<subscription uuid>"] }
+  ]
+}`;
+
 interface FilterEditorProps {
   isOpen: boolean;
   isCreating: boolean;
@@ -20,6 +36,18 @@ interface FilterEditorProps {
   onPatternChange: (pattern: string) => void;
   except: string;
   onExceptChange: (except: string) => void;
+  caseInsensitive: boolean;
+  onCaseInsensitiveChange: (value: boolean) => void;
+  wholeWord: boolean;
+  onWholeWordChange: (value: boolean) => void;
+  sort: FilterSort;
+  onSortChange: (sort: FilterSort) => void;
+  limit: string;
+  onLimitChange: (limit: string) => void;
+  subscriptions: string[];
+  onSubscriptionsChange: (subscriptions: string[]) => void;
+  conditions: string;
+  onConditionsChange: (conditions: string) => void;
   uuid: string;
   onClose: () => void;
   onSave: () => void;
@@ -47,6 +75,18 @@ export function FilterEditor({
   onPatternChange,
   except,
   onExceptChange,
+  caseInsensitive,
+  onCaseInsensitiveChange,
+  wholeWord,
+  onWholeWordChange,
+  sort,
+  onSortChange,
+  limit,
+  onLimitChange,
+  subscriptions,
+  onSubscriptionsChange,
+  conditions,
+  onConditionsChange,
   uuid,
   onClose,
   onSave,
@@ -56,6 +96,32 @@ export function FilterEditor({
   deleteDialogOpen,
   onDeleteDialogChange,
 }: FilterEditorProps) {
+  const previewMutation = useFilterPreview();
+  const { data: subscribeList } = useSubscribeList();
+  const preview = previewMutation.data;
+
+  let conditionsError: string | null = null;
+  try {
+    parseFilterConditions(conditions);
+  } catch (error) {
+    conditionsError = error instanceof Error ? error.message : "Invalid JSON";
+  }
+
+  const handlePreview = () => {
+    const parsedLimit = Number.parseInt(limit, 10);
+    previewMutation.mutate({
+      filter_type: filterType,
+      pattern,
+      except: except.trim() || undefined,
+      case_insensitive: caseInsensitive,
+      whole_word: wholeWord,
+      sort,
+      limit: parsedLimit > 0 ? parsedLimit : undefined,
+      subscriptions,
+      conditions: parseFilterConditions(conditions),
+    });
+  };
+
   return (
     <FormEditor
       isOpen={isOpen}
@@ -115,21 +181,177 @@ export function FilterEditor({
           </p>
         </div>
 
-        {filterType === "simple" && (
+        <div className="space-y-2">
+          <Label htmlFor="except">Except (Optional)</Label>
+          <Textarea
+            id="except"
+            value={except}
+            onChange={(e) => onExceptChange(e.target.value)}
+            placeholder={filterType === "simple" ? "测试|test|trial" : "(?i)test|trial"}
+            className="font-mono text-sm min-h-[100px] dark:bg-background"
+          />
+          <p className="text-sm text-muted-foreground">
+            {filterType === "simple"
+              ? "Exclude outbounds that contain these keywords (separated by | character)"
+              : "Exclude outbounds matching this regular expression"}
+          </p>
+        </div>
+
+        <div className="flex items-center justify-between">
+          <Label htmlFor="case-insensitive">Case Insensitive</Label>
+          <Switch
+            id="case-insensitive"
+            checked={caseInsensitive}
+            onCheckedChange={onCaseInsensitiveChange}
+          />
+        </div>
+
+        <div className="flex items-center justify-between">
+          <div className="space-y-1">
+            <Label htmlFor="whole-word">Whole Word</Label>
+            <p className="text-sm text-muted-foreground">
+              Only match whole words, so "US" does not match "AUS"
+            </p>
+          </div>
+          <Switch
+            id="whole-word"
+            checked={wholeWord}
+            onCheckedChange={onWholeWordChange}
+          />
+        </div>
+
+        <div className="grid grid-cols-2 gap-4">
+          <div className="space-y-2">
+            <Label htmlFor="filter-sort">Sort</Label>
+            <Select
+              value={sort}
+              onValueChange={(value) => onSortChange(value as FilterSort)}
+            >
+              <SelectTrigger id="filter-sort" className="dark:bg-background">
+                <SelectValue />
+              </SelectTrigger>
+              <SelectContent>
+                <SelectItem value="subscription">Subscription order</SelectItem>
+                <SelectItem value="name">Name</SelectItem>
+                <SelectItem value="natural">Name (natural numbers)</SelectItem>
+              </SelectContent>
+            </Select>
+          </div>
           <div className="space-y-2">
-            <Label htmlFor="except">Except (Optional)</Label>
-            <Textarea
-              id="except"
-              value={except}
-              onChange={(e) => onExceptChange(e.target.value)}
-              placeholder="测试|test|trial"
-              className="font-mono text-sm min-h-[100px] dark:bg-background"
+            <Label htmlFor="filter-limit">Limit (Optional)</Label>
+            <Input
+              id="filter-limit"
+              type="number"
+              min={1}
+              value={limit}
+              onChange={(e) => onLimitChange(e.target.value)}
+              placeholder="All nodes"
+              className="dark:bg-background"
             />
+          </div>
+        </div>
+
+        <div className="space-y-2">
+          <Label>Subscriptions</Label>
+          <p className="text-sm text-muted-foreground">
+            Only take nodes from the selected subscriptions. Leave all unchecked
+            to use every subscription.
+          </p>
+          <div className="grid grid-cols-1 sm:grid-cols-2 gap-2">
+            {subscribeList?.map((subscribe) => (
+              <div key={subscribe.uuid} className="flex items-center gap-2">
+                <Checkbox
+                  id={`filter-subscription-${subscribe.uuid}`}
+                  checked={subscriptions.includes(subscribe.uuid)}
+                  onCheckedChange={(checked) =>
+                    onSubscriptionsChange(
+                      checked === true
+                        ? [...subscriptions, subscribe.uuid]
+                        : subscriptions.filter((uuid) => uuid !== subscribe.uuid),
+                    )
+                  }
+                />
+                <Label
+                  htmlFor={`filter-subscription-${subscribe.uuid}`}
+                  className="cursor-pointer"
+                >
+                  {subscribe.name}
+                </Label>
+              </div>
+            ))}
+          </div>
+        </div>
+
+        <div className="space-y-2">
+          <Label htmlFor="conditions">Node Conditions (Optional)</Label>
+          <Textarea
+            id="conditions"
+            value={conditions}
+            onChange={(e) => onConditionsChange(e.target.value)}
+            placeholder={CONDITIONS_PLACEHOLDER}
+            className="font-mono text-sm min-h-[160px] dark:bg-background"
+          />
+          {conditionsError ? (
+            <p className="text-sm text-destructive">{conditionsError}</p>
+          ) : (
             <p className="text-sm text-muted-foreground">
-              Exclude outbounds that contain these keywords (separated by | character)
+              Match on type, server (domain or CIDR), port ranges, transport,
+              tls, reality or subscription. Rules combine with "and" (default)
+              or "or"; a non-empty pattern counts as one more rule.
             </p>
+          )}
+        </div>
+
+        <div className="space-y-2">
+          <div className="flex items-center justify-between">
+            <Label>Preview</Label>
+            <Button
+              variant="outline"
+              size="sm"
+              onClick={handlePreview}
+              disabled={
+                (!pattern.trim() && !conditions.trim()) ||
+                conditionsError !== null ||
+                previewMutation.isPending
+              }
+            >
+              {previewMutation.isPending ? "Matching..." : "Preview Matches"}
+            </Button>
           </div>
-        )}
+          {previewMutation.isError && (
+            <p className="text-sm text-destructive">Failed to preview filter</p>
+          )}
+          {preview && (
+            <div className="rounded-md border p-3 space-y-3 text-sm">
+              <p className="text-muted-foreground">
+                {preview.matched.length} node
+                {preview.matched.length !== 1 ? "s" : ""} matched
+              </p>
+              {preview.subscriptions.map((subscription) => (
+                <div key={subscription.uuid} className="space-y-1">
+                  <p className="font-medium">
+                    {subscription.name}
+                    <span className="ml-2 text-xs text-muted-foreground">
+                      {subscription.matched.length} matched ·{" "}
+                      {subscription.excluded.length} excluded ·{" "}
+                      {subscription.unmatched.length} not matched
+                    </span>
+                  </p>
+                  {subscription.matched.length > 0 && (
+                    <p className="font-mono text-xs break-words">
+                      {subscription.matched.join(", ")}
+                    </p>
+                  )}
+                  {subscription.excluded.length > 0 && (
+                    <p className="font-mono text-xs break-words text-muted-foreground line-through">
+                      {subscription.excluded.join(", ")}
+                    </p>
+                  )}
+                </div>
+              ))}
+            </div>
+          )}
+        </div>
       </div>
     </FormEditor>
   );
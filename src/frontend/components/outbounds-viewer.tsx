@@ -11,12 +11,19 @@ import { ScrollArea } from "@/components/ui/scroll-area";
 import { Badge } from "@/components/ui/badge";
 import { IconCode, IconLayoutGrid, IconX } from "@tabler/icons-react";
 import { motion } from "framer-motion";
-import type { OutboundDto } from "@/api/subscribe/outbounds";
+import type {
+  ExcludedNode,
+  OutboundDto,
+  ParseDiagnostic,
+} from "@/api/subscribe/outbounds";
 
 interface OutboundsViewerProps {
   isOpen: boolean;
   onClose: () => void;
   outbounds: OutboundDto[];
+  diagnostics?: ParseDiagnostic[];
+  excluded?: ExcludedNode[];
+  enabled?: boolean;
   isLoading: boolean;
   subscribeName: string;
 }
@@ -25,6 +32,9 @@ export function OutboundsViewer({
   isOpen,
   onClose,
   outbounds,
+  diagnostics = [],
+  excluded = [],
+  enabled = true,
   isLoading,
   subscribeName,
 }: OutboundsViewerProps) {
@@ -52,6 +62,11 @@ export function OutboundsViewer({
               <DialogTitle>Subscription Outbounds</DialogTitle>
               <DialogDescription>
                 Viewing {outbounds.length} outbound{outbounds.length !== 1 ? "s" : ""} from "{subscribeName}"
+                {diagnostics.length > 0 &&
+                  ` · ${diagnostics.length} line${diagnostics.length !== 1 ? "s" : ""} skipped`}
+                {excluded.length > 0 &&
+                  ` · ${excluded.length} info node${excluded.length !== 1 ? "s" : ""} dropped`}
+                {!enabled && " · Subscription is disabled, its nodes are not used"}
               </DialogDescription>
             </div>
             <Button
@@ -16,7 +16,15 @@ import {
 } from "@/components/ui/dropdown-menu";
 import { Input } from "@/components/ui/input";
 import { Label } from "@/components/ui/label";
+import {
+  Select,
+  SelectContent,
+  SelectItem,
+  SelectTrigger,
+  SelectValue,
+} from "@/components/ui/select";
 import { Switch } from "@/components/ui/switch";
+import { Textarea } from "@/components/ui/textarea";
 import {
   Tooltip,
   TooltipContent,
@@ -24,21 +32,43 @@ import {
 } from "@/components/ui/tooltip";
 import { IconDeviceFloppy, IconTrash, IconRefresh, IconEye } from "@tabler/icons-react";
 import { motion, AnimatePresence } from "framer-motion";
+import { useEffect, useState } from "react";
 import { formatTimeAgo, formatDateTime } from "@/lib/time";
+import type {
+  SubscriptionSource,
+  TagCollisionStrategy,
+} from "@/types/subscribe";
 
 interface SubscribeEditorProps {
   isOpen: boolean;
   isCreating: boolean;
   name: string;
   onNameChange: (name: string) => void;
+  source: SubscriptionSource;
+  onSourceChange: (source: SubscriptionSource) => void;
   subscriptionUrl: string;
   onSubscriptionUrlChange: (url: string) => void;
+  /** Name of the uploaded file, if any */
+  fileName?: string;
+  onFileChange: (file: File | null) => void;
+  pastedContent: string;
+  onPastedContentChange: (content: string) => void;
   websiteUrl: string;
   onWebsiteUrlChange: (url: string) => void;
   content: string;
   lastUpdated: string | null;
   enabled: boolean;
   onEnabledChange: (enabled: boolean) => void;
+  priority: number;
+  onPriorityChange: (priority: number) => void;
+  labels: string[];
+  onLabelsChange: (labels: string[]) => void;
+  notes: string;
+  onNotesChange: (notes: string) => void;
+  tagTemplate: string;
+  onTagTemplateChange: (template: string) => void;
+  tagCollision: TagCollisionStrategy;
+  onTagCollisionChange: (strategy: TagCollisionStrategy) => void;
   uuid: string;
   onClose: () => void;
   onSave: () => void;
@@ -57,14 +87,30 @@ export function SubscribeEditor({
   isCreating,
   name,
   onNameChange,
+  source,
+  onSourceChange,
   subscriptionUrl,
   onSubscriptionUrlChange,
+  fileName,
+  onFileChange,
+  pastedContent,
+  onPastedContentChange,
   websiteUrl,
   onWebsiteUrlChange,
   content: _content,
   lastUpdated,
   enabled,
   onEnabledChange,
+  priority,
+  onPriorityChange,
+  labels,
+  onLabelsChange,
+  notes,
+  onNotesChange,
+  tagTemplate,
+  onTagTemplateChange,
+  tagCollision,
+  onTagCollisionChange,
   uuid,
   onClose,
   onSave,
@@ -77,6 +123,13 @@ export function SubscribeEditor({
   deleteDialogOpen,
   onDeleteDialogChange,
 }: SubscribeEditorProps) {
+  // Keep the raw text so a trailing comma survives while typing
+  const [labelsText, setLabelsText] = useState(labels.join(", "));
+  // biome-ignore lint/correctness/useExhaustiveDependencies: only reset when another subscription is opened
+  useEffect(() => {
+    setLabelsText(labels.join(", "));
+  }, [uuid]);
+
   return (
     <AnimatePresence>
       {isOpen && (
@@ -124,7 +177,7 @@ export function SubscribeEditor({
                     </Tooltip>
                   )}
 
-                  {!isCreating && onRefresh && (
+                  {!isCreating && onRefresh && source === "url" && (
                     <Tooltip>
                       <TooltipTrigger asChild>
                         <Button
@@ -217,17 +270,70 @@ export function SubscribeEditor({
                   </div>
 
                   <div className="space-y-2">
-                    <Label htmlFor="subscription-url">Subscription URL</Label>
-                    <Input
-                      id="subscription-url"
-                      value={subscriptionUrl}
-                      onChange={(e) => onSubscriptionUrlChange(e.target.value)}
-                      placeholder="https://..."
-                      type="url"
-                      className="dark:bg-background"
-                    />
+                    <Label htmlFor="subscription-source">Source</Label>
+                    <Select
+                      value={source}
+                      onValueChange={(value) =>
+                        onSourceChange(value as SubscriptionSource)
+                      }
+                    >
+                      <SelectTrigger id="subscription-source" className="dark:bg-background">
+                        <SelectValue />
+                      </SelectTrigger>
+                      <SelectContent>
+                        <SelectItem value="url">Remote URL</SelectItem>
+                        <SelectItem value="file">Uploaded file</SelectItem>
+                        <SelectItem value="inline">Pasted text</SelectItem>
+                      </SelectContent>
+                    </Select>
                   </div>
 
+                  {source === "url" && (
+                    <div className="space-y-2">
+                      <Label htmlFor="subscription-url">Subscription URL</Label>
+                      <Input
+                        id="subscription-url"
+                        value={subscriptionUrl}
+                        onChange={(e) => onSubscriptionUrlChange(e.target.value)}
+                        placeholder="https://..."
+                        type="url"
+                        className="dark:bg-background"
+                      />
+                    </div>
+                  )}
+
+                  {source === "file" && (
+                    <div className="space-y-2">
+                      <Label htmlFor="subscription-file">Subscription File</Label>
+                      <Input
+                        id="subscription-file"
+                        type="file"
+                        accept=".txt,.yaml,.yml,.json,.conf"
+                        onChange={(e) => onFileChange(e.target.files?.[0] ?? null)}
+                        className="dark:bg-background"
+                      />
+                      {fileName && (
+                        <p className="text-sm text-muted-foreground">
+                          Current file: {fileName}
+                        </p>
+                      )}
+                    </div>
+                  )}
+
+                  {source === "inline" && (
+                    <div className="space-y-2">
+                      <Label htmlFor="subscription-content">Content</Label>
+                      <Textarea
+                        id="subscription-content"
+                        value={pastedContent}
+                        onChange={(e) => onPastedContentChange(e.target.value)}
+                        placeholder="vless://... one share link per line, or a Base64 / Clash / sing-box subscription body"
+                        rows={8}
+                        className="font-mono dark:bg-background"
+                      />
+                    </div>
+                  )}
+
                   <div className="space-y-2">
                     <Label htmlFor="website-url">Website URL (Optional)</Label>
                     <Input
@@ -239,6 +345,85 @@ export function SubscribeEditor({
                       className="dark:bg-background"
                     />
                   </div>
+
+                  <div className="space-y-2">
+                    <Label htmlFor="subscription-priority">Priority</Label>
+                    <Input
+                      id="subscription-priority"
+                      type="number"
+                      value={priority}
+                      onChange={(e) => onPriorityChange(Number(e.target.value) || 0)}
+                      className="dark:bg-background"
+                    />
+                    <p className="text-sm text-muted-foreground">
+                      Higher priority keeps its copy when duplicate nodes are removed
+                    </p>
+                  </div>
+
+                  <div className="space-y-2">
+                    <Label htmlFor="subscription-labels">Labels</Label>
+                    <Input
+                      id="subscription-labels"
+                      value={labelsText}
+                      onChange={(e) => {
+                        setLabelsText(e.target.value);
+                        onLabelsChange(
+                          e.target.value
+                            .split(",")
+                            .map((label) => label.trim())
+                            .filter(Boolean),
+                        );
+                      }}
+                      placeholder="airport, backup"
+                      className="dark:bg-background"
+                    />
+                  </div>
+
+                  <div className="space-y-2">
+                    <Label htmlFor="subscription-notes">Notes</Label>
+                    <Textarea
+                      id="subscription-notes"
+                      value={notes}
+                      onChange={(e) => onNotesChange(e.target.value)}
+                      maxLength={2000}
+                      rows={3}
+                      className="dark:bg-background"
+                    />
+                  </div>
+
+                  <div className="space-y-2">
+                    <Label htmlFor="tag-template">Tag Template</Label>
+                    <Input
+                      id="tag-template"
+                      value={tagTemplate}
+                      onChange={(e) => onTagTemplateChange(e.target.value)}
+                      placeholder="{name}-{sub}"
+                      className="font-mono dark:bg-background"
+                    />
+                    <p className="text-sm text-muted-foreground">
+                      Placeholders: {"{name}"}, {"{sub}"}, {"{index}"},{" "}
+                      {"{protocol}"}, {"{country}"}
+                    </p>
+                  </div>
+
+                  <div className="space-y-2">
+                    <Label htmlFor="tag-collision">On Duplicate Tag</Label>
+                    <Select
+                      value={tagCollision}
+                      onValueChange={(value) =>
+                        onTagCollisionChange(value as TagCollisionStrategy)
+                      }
+                    >
+                      <SelectTrigger id="tag-collision" className="dark:bg-background">
+                        <SelectValue />
+                      </SelectTrigger>
+                      <SelectContent>
+                        <SelectItem value="suffix">Append a counter (-2, -3, ...)</SelectItem>
+                        <SelectItem value="skip">Skip the node</SelectItem>
+                        <SelectItem value="error">Fail generation</SelectItem>
+                      </SelectContent>
+                    </Select>
+                  </div>
                 </div>
               </div>
             </motion.div>
@@ -262,7 +447,7 @@ export function SubscribeEditor({
                       <span>View Outbounds</span>
                     </DropdownMenuItem>
                   )}
-                  {!isCreating && onRefresh && (
+                  {!isCreating && onRefresh && source === "url" && (
                     <DropdownMenuItem
                       onClick={onRefresh}
                       disabled={isRefreshing}
@@ -2,6 +2,7 @@ import { useConfigCreate } from "@/api/config/create";
 import { useConfigDelete } from "@/api/config/delete";
 import { useConfigList, type ConfigListDto } from "@/api/config/list";
 import { useConfigUpdate } from "@/api/config/update";
+import { validateConfig } from "@/api/config/validate";
 import { AppPage } from "@/components/app-page";
 import { ConfigManagementCard } from "@/components/config-management-card";
 import { ConfigForm, type SingBoxConfig } from "@/components/config-form";
@@ -25,6 +26,7 @@ import {
   IconCheck,
   IconTrash,
   IconCopy,
+  IconShieldCheck,
 } from "@tabler/icons-react";
 import { createFileRoute } from "@tanstack/react-router";
 import { useState } from "react";
@@ -144,6 +146,33 @@ function RouteComponent() {
     }
   };
 
+  const handleValidateConfig = async (config: ConfigListDto) => {
+    try {
+      const report = await validateConfig(config.uuid);
+      const issues = [...report.errors, ...report.warnings];
+      const description = issues.map((issue) => issue.message).join("\n");
+      if (!report.valid) {
+        toast.error(
+          `${config.name}: ${report.errors.length} error(s), ${report.warnings.length} warning(s)`,
+          { description },
+        );
+      } else if (report.warnings.length > 0) {
+        toast.warning(`${config.name}: ${report.warnings.length} warning(s)`, {
+          description,
+        });
+      } else {
+        toast.success(`${config.name} is valid`);
+      }
+    } catch (error: unknown) {
+      console.error("Failed to validate config:", error);
+      const errorMessage = await extractErrorMessage(
+        error,
+        "Failed to validate config",
+      );
+      toast.error(errorMessage);
+    }
+  };
+
   const copyDownloadLink = async (configId: string) => {
     try {
       const downloadUrl = `${window.location.origin}/download/${configId}`;
@@ -246,6 +275,16 @@ function RouteComponent() {
                   >
                     <IconCopy className="size-4" />
                   </Button>
+                  <Button
+                    variant="ghost"
+                    size="sm"
+                    onClick={(e) => {
+                      e.stopPropagation();
+                      handleValidateConfig(config);
+                    }}
+                  >
+                    <IconShieldCheck className="size-4" />
+                  </Button>
                   <Button
                     variant="ghost"
                     size="sm"
@@ -1,4 +1,8 @@
-import { createFilter } from "@/api/filter/create";
+import {
+  type FilterSort,
+  createFilter,
+  parseFilterConditions,
+} from "@/api/filter/create";
 import { useFilterDelete } from "@/api/filter/delete";
 import { useFilterList } from "@/api/filter/list";
 import { useFilterUpdate } from "@/api/filter/update";
@@ -8,6 +12,7 @@ import { EmptyState } from "@/components/empty-state";
 import { FilterEditor } from "@/components/filter-editor";
 import { SkeletonGrid } from "@/components/skeleton-grid";
 import { Button } from "@/components/ui/button";
+import { extractErrorMessage } from "@/lib/error";
 import { IconFilter } from "@tabler/icons-react";
 import { createFileRoute } from "@tanstack/react-router";
 import { AnimatePresence, motion } from "framer-motion";
@@ -38,6 +43,12 @@ function RouteComponent() {
   );
   const [editPattern, setEditPattern] = useState("");
   const [editExcept, setEditExcept] = useState("");
+  const [editCaseInsensitive, setEditCaseInsensitive] = useState(false);
+  const [editWholeWord, setEditWholeWord] = useState(false);
+  const [editSort, setEditSort] = useState<FilterSort>("subscription");
+  const [editLimit, setEditLimit] = useState("");
+  const [editSubscriptions, setEditSubscriptions] = useState<string[]>([]);
+  const [editConditions, setEditConditions] = useState("");
   const [editUuid, setEditUuid] = useState("");
 
   useEffect(() => {
@@ -46,6 +57,16 @@ function RouteComponent() {
       setEditFilterType(selectedFilter.filter_type);
       setEditPattern(selectedFilter.pattern);
       setEditExcept(selectedFilter.except || "");
+      setEditCaseInsensitive(selectedFilter.case_insensitive ?? false);
+      setEditWholeWord(selectedFilter.whole_word ?? false);
+      setEditSort(selectedFilter.sort ?? "subscription");
+      setEditLimit(selectedFilter.limit?.toString() ?? "");
+      setEditSubscriptions(selectedFilter.subscriptions ?? []);
+      setEditConditions(
+        selectedFilter.conditions
+          ? JSON.stringify(selectedFilter.conditions, null, 2)
+          : "",
+      );
       setEditUuid(selectedFilter.uuid);
     }
   }, [selectedFilter, isCreating]);
@@ -79,6 +100,12 @@ function RouteComponent() {
     setEditFilterType("simple");
     setEditPattern("");
     setEditExcept("");
+    setEditCaseInsensitive(false);
+    setEditWholeWord(false);
+    setEditSort("subscription");
+    setEditLimit("");
+    setEditSubscriptions([]);
+    setEditConditions("");
     setEditUuid(uuidv4());
     setFocusMode(true);
   };
@@ -94,7 +121,19 @@ function RouteComponent() {
       toast.error("Name cannot be empty");
       return;
     }
-    if (!editPattern.trim()) {
+    let conditions: ReturnType<typeof parseFilterConditions>;
+    try {
+      conditions = parseFilterConditions(editConditions);
+    } catch {
+      toast.error("Node conditions are not valid JSON");
+      return;
+    }
+    const limit = editLimit.trim() ? Number(editLimit) : undefined;
+    if (limit !== undefined && (!Number.isInteger(limit) || limit < 1)) {
+      toast.error("Limit must be a positive whole number");
+      return;
+    }
+    if (!editPattern.trim() && !conditions) {
       toast.error("Pattern cannot be empty");
       return;
     }
@@ -107,6 +146,12 @@ function RouteComponent() {
           filter_type: editFilterType,
           pattern: editPattern,
           except: editExcept.trim() || undefined,
+          case_insensitive: editCaseInsensitive,
+          whole_word: editWholeWord,
+          sort: editSort,
+          limit,
+          subscriptions: editSubscriptions,
+          conditions,
         });
         toast.success("Filter created successfully");
         setIsCreating(false);
@@ -120,15 +165,24 @@ function RouteComponent() {
           filter_type: editFilterType,
           pattern: editPattern,
           except: editExcept.trim() || undefined,
+          case_insensitive: editCaseInsensitive,
+          whole_word: editWholeWord,
+          sort: editSort,
+          limit,
+          subscriptions: editSubscriptions,
+          conditions,
         });
         toast.success("Filter updated successfully");
         await refetchList();
       }
     } catch (error) {
       console.error(error);
-      toast.error(
+      // Invalid patterns come back as a 400 with the regex error
+      const errorMessage = await extractErrorMessage(
+        error,
         isCreating ? "Failed to create filter" : "Failed to update filter",
       );
+      toast.error(errorMessage);
     }
   };
 
@@ -229,6 +283,18 @@ function RouteComponent() {
         onPatternChange={setEditPattern}
         except={editExcept}
         onExceptChange={setEditExcept}
+        caseInsensitive={editCaseInsensitive}
+        onCaseInsensitiveChange={setEditCaseInsensitive}
+        wholeWord={editWholeWord}
+        onWholeWordChange={setEditWholeWord}
+        sort={editSort}
+        onSortChange={setEditSort}
+        limit={editLimit}
+        onLimitChange={setEditLimit}
+        subscriptions={editSubscriptions}
+        onSubscriptionsChange={setEditSubscriptions}
+        conditions={editConditions}
+        onConditionsChange={setEditConditions}
         uuid={isCreating ? editUuid : selectedFilter?.uuid || ""}
         onClose={handleExitFocus}
         onSave={handleSave}
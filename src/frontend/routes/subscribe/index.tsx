@@ -5,6 +5,7 @@ import { useSubscribeRefresh } from "@/api/subscribe/refresh";
 import { useSubscribeOutbounds } from "@/api/subscribe/outbounds";
 import { useSubscribeReorder } from "@/api/subscribe/reorder";
 import { createSubscribe } from "@/api/subscribe/create";
+import { pasteSubscribe, uploadSubscribe } from "@/api/subscribe/import";
 import { AppPage } from "@/components/app-page";
 import { ConfigCard } from "@/components/config-card";
 import { EmptyState } from "@/components/empty-state";
@@ -57,8 +58,9 @@ function RouteComponent() {
   const refreshSubscribeMutation = useSubscribeRefresh();
   const reorderMutation = useSubscribeReorder();
 
-  const { data: outbounds = [], isLoading: isLoadingOutbounds } =
+  const { data: outboundsResult, isLoading: isLoadingOutbounds } =
     useSubscribeOutbounds(outboundsViewerOpen ? selectedUuid : null);
+  const outbounds = outboundsResult?.outbounds ?? [];
 
   const [editName, setEditName] = useState("");
   const [editMetadata, setEditMetadata] = useState<SubscriptionMetadata>({
@@ -69,6 +71,8 @@ function RouteComponent() {
     enabled: true,
   });
   const [editUuid, setEditUuid] = useState("");
+  const [pendingFile, setPendingFile] = useState<File | null>(null);
+  const [pastedContent, setPastedContent] = useState("");
 
   useEffect(() => {
     if (selectedSubscribe && !isCreating) {
@@ -76,6 +80,8 @@ function RouteComponent() {
       const metadata = parseSubscriptionJson(selectedSubscribe.json);
       setEditMetadata(metadata);
       setEditUuid(selectedSubscribe.uuid);
+      setPendingFile(null);
+      setPastedContent(metadata.source === "inline" ? metadata.content : "");
     }
   }, [selectedSubscribe, isCreating]);
 
@@ -124,6 +130,8 @@ function RouteComponent() {
       enabled: true,
     });
     setEditUuid(uuidv4());
+    setPendingFile(null);
+    setPastedContent("");
     setFocusMode(true);
   };
 
@@ -138,13 +146,22 @@ function RouteComponent() {
   };
 
   const handleSave = async () => {
-    if (!editMetadata.subscription_url) {
-      toast.error("Subscription URL is required");
-      return;
-    }
+    const source = editMetadata.source ?? "url";
+    if (source === "url") {
+      if (!editMetadata.subscription_url) {
+        toast.error("Subscription URL is required");
+        return;
+      }
 
-    if (!validateUrl(editMetadata.subscription_url)) {
-      toast.error("Invalid subscription URL");
+      if (!validateUrl(editMetadata.subscription_url)) {
+        toast.error("Invalid subscription URL");
+        return;
+      }
+    } else if (source === "file" && isCreating && !pendingFile) {
+      toast.error("Choose a subscription file to upload");
+      return;
+    } else if (source === "inline" && !pastedContent.trim()) {
+      toast.error("Paste the subscription content");
       return;
     }
 
@@ -163,6 +180,7 @@ function RouteComponent() {
           name: editName,
           json: jsonString,
         });
+        await importLocalContent(editUuid);
         toast.success("Subscribe created successfully");
         setIsCreating(false);
         await refetchList();
@@ -176,16 +194,31 @@ function RouteComponent() {
           name: editName,
           json: jsonString,
         });
+        await importLocalContent(selectedUuid);
         toast.success("Subscribe updated successfully");
         await refetchList();
       }
     } catch (error) {
       console.error(error);
-      toast.error(
-        isCreating
-          ? "Failed to create subscribe"
-          : "Failed to update subscribe",
+      const errorMessage = await extractErrorMessage(
+        error,
+        isCreating ? "Failed to create subscribe" : "Failed to update subscribe",
       );
+      toast.error(errorMessage);
+    }
+  };
+
+  /** Send the chosen file or pasted text for local (non-URL) subscriptions */
+  const importLocalContent = async (uuid: string) => {
+    const source = editMetadata.source ?? "url";
+    if (source === "file" && pendingFile) {
+      await uploadSubscribe(uuid, editName, pendingFile);
+      setPendingFile(null);
+    } else if (
+      source === "inline" &&
+      pastedContent.trim() !== editMetadata.content.trim()
+    ) {
+      await pasteSubscribe({ uuid, name: editName, content: pastedContent });
     }
   };
 
@@ -367,20 +400,22 @@ function RouteComponent() {
                         <IconEye className="size-4" />
                       </button>
                     )}
-                    <button
-                      type="button"
-                      onClick={(e) => {
-                        e.stopPropagation();
-                        handleRefresh(subscribe.uuid);
-                      }}
-                      disabled={refreshSubscribeMutation.isPending}
-                      className="p-1.5 rounded-md hover:bg-primary/10 text-primary/70 hover:text-primary transition-all duration-150 disabled:opacity-50 disabled:cursor-not-allowed"
-                      title="Refresh subscription"
-                    >
-                      <IconRefresh
-                        className={`size-4 ${refreshSubscribeMutation.isPending ? "animate-spin" : ""}`}
-                      />
-                    </button>
+                    {(metadata.source ?? "url") === "url" && (
+                      <button
+                        type="button"
+                        onClick={(e) => {
+                          e.stopPropagation();
+                          handleRefresh(subscribe.uuid);
+                        }}
+                        disabled={refreshSubscribeMutation.isPending}
+                        className="p-1.5 rounded-md hover:bg-primary/10 text-primary/70 hover:text-primary transition-all duration-150 disabled:opacity-50 disabled:cursor-not-allowed"
+                        title="Refresh subscription"
+                      >
+                        <IconRefresh
+                          className={`size-4 ${refreshSubscribeMutation.isPending ? "animate-spin" : ""}`}
+                        />
+                      </button>
+                    )}
                   </>
                 }
               />
@@ -395,6 +430,14 @@ function RouteComponent() {
         isCreating={isCreating}
         name={editName}
         onNameChange={setEditName}
+        source={editMetadata.source ?? "url"}
+        onSourceChange={(source) =>
+          setEditMetadata({ ...editMetadata, source })
+        }
+        fileName={pendingFile?.name ?? editMetadata.file_name}
+        onFileChange={setPendingFile}
+        pastedContent={pastedContent}
+        onPastedContentChange={setPastedContent}
         subscriptionUrl={editMetadata.subscription_url}
         onSubscriptionUrlChange={(url) =>
           setEditMetadata({ ...editMetadata, subscription_url: url })
@@ -409,6 +452,26 @@ function RouteComponent() {
         onEnabledChange={(enabled) =>
           setEditMetadata({ ...editMetadata, enabled })
         }
+        priority={editMetadata.priority ?? 0}
+        onPriorityChange={(priority) =>
+          setEditMetadata({ ...editMetadata, priority })
+        }
+        labels={editMetadata.labels ?? []}
+        onLabelsChange={(labels) =>
+          setEditMetadata({ ...editMetadata, labels })
+        }
+        notes={editMetadata.notes ?? ""}
+        onNotesChange={(notes) =>
+          setEditMetadata({ ...editMetadata, notes: notes || undefined })
+        }
+        tagTemplate={editMetadata.tag_template || ""}
+        onTagTemplateChange={(tag_template) =>
+          setEditMetadata({ ...editMetadata, tag_template })
+        }
+        tagCollision={editMetadata.tag_collision || "suffix"}
+        onTagCollisionChange={(tag_collision) =>
+          setEditMetadata({ ...editMetadata, tag_collision })
+        }
         uuid={isCreating ? editUuid : selectedSubscribe?.uuid || ""}
         onClose={handleExitFocus}
         onSave={handleSave}
@@ -427,6 +490,9 @@ function RouteComponent() {
         isOpen={outboundsViewerOpen}
         onClose={() => setOutboundsViewerOpen(false)}
         outbounds={outbounds}
+        diagnostics={outboundsResult?.diagnostics ?? []}
+        excluded={outboundsResult?.excluded ?? []}
+        enabled={outboundsResult?.enabled ?? true}
         isLoading={isLoadingOutbounds}
         subscribeName={selectedSubscribe?.name || ""}
       />
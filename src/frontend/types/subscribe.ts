@@ -1,15 +1,74 @@
+export type TagCollisionStrategy = "suffix" | "skip" | "error";
+
+/** Regex find/replace run on the original node name; replacement may use $1 */
+export interface RenameRule {
+  pattern: string;
+  replacement: string;
+}
+
+/** Exclusion of informational pseudo-nodes such as "剩余流量" or "到期时间" */
+export interface PseudoNodeFilter {
+  /** Set to false to keep every node (default true) */
+  enabled?: boolean;
+  /** Name keywords; replaces the built-in list when set */
+  keywords?: string[];
+  /** Drop nodes pointing at loopback/private addresses (default true) */
+  exclude_private_addresses?: boolean;
+  /** Server ports treated as dummy (default [0, 1]) */
+  excluded_ports?: number[];
+}
+
+/** Where the subscription content comes from */
+export type SubscriptionSource = "url" | "file" | "inline";
+
 export interface SubscriptionMetadata {
+  /** Empty for uploaded or pasted subscriptions */
   subscription_url: string;
+  /** Content source (default "url"); file and inline sources are never fetched */
+  source?: SubscriptionSource;
+  /** Original name of the uploaded file */
+  file_name?: string;
   website_url?: string;
   content: string;
   last_updated: string | null;
+  /** Disabled subscriptions contribute no nodes to configs, previews or usage checks */
   enabled?: boolean;
+  /** Higher priority wins when deduplicating nodes across subscriptions (default 0) */
+  priority?: number;
+  /** Free-form labels (unique, up to 32 characters each) */
+  labels?: string[];
+  /** Notes, up to 2000 characters */
+  notes?: string;
+  /** Auto-refresh interval in minutes, 0 disables scheduled refresh (default 720) */
+  refresh_interval_minutes?: number;
+  last_failure_at?: string | null;
+  last_error?: string | null;
+  /** User-Agent sent when fetching (default: a desktop Chrome UA) */
+  user_agent?: string;
+  /** Extra request headers */
+  headers?: Record<string, string>;
+  /** Fetch timeout in seconds (default 30) */
+  timeout_seconds?: number;
+  /** http(s):// or socks5(h):// proxy used for fetching */
+  proxy?: string;
+  /** Node tag template, placeholders {name} {sub} {index} {protocol} {country} (default "{name}-{sub}") */
+  tag_template?: string;
+  /** What to do when a rendered tag is already taken (default "suffix") */
+  tag_collision?: TagCollisionStrategy;
+  /** Ordered rename rules, applied after the global rules and before tag_template */
+  rename_rules?: RenameRule[];
+  /** Informational pseudo-node exclusion (enabled by default) */
+  pseudo_node_filter?: PseudoNodeFilter;
+  /** Parsed/rejected line counts from the last refresh */
+  parse_summary?: { ok: number; failed: number; unsupported: number };
+  [key: string]: unknown;
 }
 
 export function parseSubscriptionJson(jsonStr: string): SubscriptionMetadata {
   try {
     const parsed = JSON.parse(jsonStr);
     return {
+      ...parsed,
       subscription_url: parsed.subscription_url || "",
       website_url: parsed.website_url,
       content: parsed.content || "",
@@ -34,14 +93,16 @@ export function stringifySubscriptionJson(
 }
 
 /**
- * Extract node names from base64-encoded subscription content.
+ * Extract node names from subscription content (base64 or plain URI list).
  * Skips metadata lines (REMARKS=, STATUS=, Traffic:, Expire:, etc.)
  * and extracts names from the URI fragment (#NodeName).
  */
 export function extractNodeNames(content: string): string[] {
   if (!content) return [];
   try {
-    const decoded = atob(content);
+    const decoded = content.includes("://")
+      ? content
+      : atob(content.replace(/\s/g, "").replace(/-/g, "+").replace(/_/g, "/"));
     const lines = decoded.split("\n").filter((line) => line.trim());
     const names: string[] = [];
     for (const line of lines) {
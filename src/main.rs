@@ -17,6 +17,9 @@ async fn main() -> anyhow::Result<()> {
   // 运行数据迁移（在接受请求前完成）
   backend::migration::run_migrations().await?;
 
+  // 启动订阅定时刷新任务
+  backend::scheduler::spawn_subscription_refresh();
+
   // 构建静态文件服务（用于 serve ./web 目录）
   let serve_dir = ServeDir::new("./web").not_found_service(ServeFile::new("./web/index.html"));
 
@@ -89,6 +92,40 @@ async fn main() -> anyhow::Result<()> {
       "/api/subscribe/outbounds",
       axum::routing::get(backend::api::subscribe::get_subscribe_outbounds),
     )
+    .route(
+      "/api/subscribe/usage",
+      axum::routing::get(backend::api::subscribe::get_subscribe_usage),
+    )
+    .route(
+      "/api/subscribe/upload",
+      axum::routing::post(backend::api::subscribe::upload_subscribe),
+    )
+    .route(
+      "/api/subscribe/paste",
+      axum::routing::post(backend::api::subscribe::paste_subscribe),
+    )
+    .route(
+      "/api/subscribe/history",
+      axum::routing::get(backend::api::subscribe_history::list_subscribe_history),
+    )
+    .route(
+      "/api/subscribe/diff",
+      axum::routing::get(backend::api::subscribe_history::get_subscribe_diff),
+    )
+    .route(
+      "/api/subscribe/rename-rules",
+      axum::routing::get(backend::api::rename_rules::get_rename_rules)
+        .put(backend::api::rename_rules::update_rename_rules),
+    )
+    .route(
+      "/api/subscribe/rename-preview",
+      axum::routing::post(backend::api::rename_rules::preview_rename),
+    )
+    .route(
+      "/api/subscribe/overrides",
+      axum::routing::get(backend::api::node_overrides::get_node_overrides)
+        .put(backend::api::node_overrides::update_node_overrides),
+    )
     .route(
       "/api/subscribe/reorder",
       axum::routing::post(backend::api::subscribe::reorder_subscribes),
@@ -114,6 +151,10 @@ async fn main() -> anyhow::Result<()> {
         .put(backend::api::filter::update_filter)
         .delete(backend::api::filter::delete_filter),
     )
+    .route(
+      "/api/filter/preview",
+      axum::routing::post(backend::api::filter::preview_filter),
+    )
     .route(
       "/api/outbound-group",
       axum::routing::post(backend::api::outbound_group::create_outbound_group)
@@ -136,6 +177,10 @@ async fn main() -> anyhow::Result<()> {
         .put(backend::api::config::update_config)
         .delete(backend::api::config::delete_config),
     )
+    .route(
+      "/api/config/{uuid}/validate",
+      axum::routing::get(backend::api::config_validation::validate_config),
+    )
     .route(
       "/api/usage-check",
       axum::routing::get(backend::api::usage_check::check_resource_usage),